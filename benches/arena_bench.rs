@@ -1,6 +1,8 @@
-use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
-use arenars::Arena;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use arenars::{Arena, TypedArena};
+use std::hint::black_box;
 
+#[allow(dead_code)]
 #[derive(Debug, Clone, Copy)]
 struct Point {
     x: f64,
@@ -97,5 +99,78 @@ fn bench_reset_reuse(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, bench_single_alloc, bench_bulk_alloc, bench_reset_reuse);
+// ── Large values: alloc vs alloc_with ────────────────────────────────────────
+
+// 16 KiB: big enough that building it on the stack and copying it into the
+// arena shows up next to constructing it in place.
+struct Large {
+    data: [u64; 2048],
+}
+
+impl Large {
+    #[inline(always)]
+    fn new() -> Self {
+        Large { data: [black_box(7); 2048] }
+    }
+}
+
+fn bench_large_alloc(c: &mut Criterion) {
+    let mut group = c.benchmark_group("large_alloc");
+    const COUNT: usize = 64;
+    let arena_size = COUNT * size_of::<Large>();
+
+    group.bench_function("arena_alloc", |b| {
+        let mut arena = Arena::new(arena_size).unwrap();
+        b.iter(|| {
+            arena.reset();
+            for _ in 0..COUNT {
+                let large = arena.alloc(Large::new()).unwrap();
+                black_box(&large.data);
+            }
+        });
+    });
+
+    group.bench_function("arena_alloc_with", |b| {
+        let mut arena = Arena::new(arena_size).unwrap();
+        b.iter(|| {
+            arena.reset();
+            for _ in 0..COUNT {
+                let large = arena.alloc_with(Large::new).unwrap();
+                black_box(&large.data);
+            }
+        });
+    });
+
+    group.bench_function("typed_arena_alloc", |b| {
+        let mut arena = TypedArena::new(COUNT).unwrap();
+        b.iter(|| {
+            arena.reset();
+            for _ in 0..COUNT {
+                let large = arena.alloc(Large::new()).unwrap();
+                black_box(&large.data);
+            }
+        });
+    });
+
+    group.bench_function("typed_arena_alloc_with", |b| {
+        let mut arena = TypedArena::new(COUNT).unwrap();
+        b.iter(|| {
+            arena.reset();
+            for _ in 0..COUNT {
+                let large = arena.alloc_with(Large::new).unwrap();
+                black_box(&large.data);
+            }
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_single_alloc,
+    bench_bulk_alloc,
+    bench_reset_reuse,
+    bench_large_alloc
+);
 criterion_main!(benches);
\ No newline at end of file
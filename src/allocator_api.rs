@@ -0,0 +1,214 @@
+//! [`Allocator`] support, so standard collections can allocate from an
+//! [`Arena`].
+//!
+//! With the `allocator-api2` feature this implements the stable
+//! [`allocator_api2`] trait (used by `hashbrown` and others); with the
+//! `nightly` feature it implements `core::alloc::Allocator` instead.
+
+use core::alloc::Layout;
+use core::ptr::{self, NonNull};
+
+#[cfg(feature = "nightly")]
+use core::alloc::{AllocError, Allocator};
+#[cfg(not(feature = "nightly"))]
+use allocator_api2::alloc::{AllocError, Allocator};
+
+use crate::Arena;
+
+fn is_aligned(ptr: NonNull<u8>, align: usize) -> bool {
+    ptr.as_ptr() as usize & (align - 1) == 0
+}
+
+/// Allocate collections in an [`Arena`] through a shared reference.
+///
+/// Memory is released all at once by [`Arena::reset`]. `deallocate` only
+/// gives bytes back when freeing the most recent allocation, and `grow`
+/// extends a block in place when it sits at the top of the bump pointer, so a
+/// single growing `Vec` does not waste the space of its old buffers.
+///
+/// ```
+/// # #[cfg(not(feature = "nightly"))] {
+/// # use arenars::Arena;
+/// use allocator_api2::vec::Vec;
+///
+/// let arena = Arena::new(1024).unwrap();
+/// let mut v = Vec::new_in(&arena);
+/// v.extend(0..100u32);
+/// assert_eq!(v.iter().sum::<u32>(), 4950);
+/// # }
+/// ```
+unsafe impl Allocator for &Arena {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let ptr = self.alloc_layout(layout).map_err(|_| AllocError)?;
+        Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        // Only the most recent allocation can be handed back; anything else
+        // stays in place until the arena is reset.
+        self.resize_last(ptr, layout.size(), 0);
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        if is_aligned(ptr, new_layout.align())
+            && self.resize_last(ptr, old_layout.size(), new_layout.size())
+        {
+            return Ok(NonNull::slice_from_raw_parts(ptr, new_layout.size()));
+        }
+
+        let new = self.allocate(new_layout)?;
+        unsafe {
+            ptr::copy_nonoverlapping(ptr.as_ptr(), new.as_ptr() as *mut u8, old_layout.size());
+        }
+        Ok(new)
+    }
+
+    unsafe fn grow_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        unsafe {
+            let new = self.grow(ptr, old_layout, new_layout)?;
+            let tail = (new.as_ptr() as *mut u8).add(old_layout.size());
+            tail.write_bytes(0, new_layout.size() - old_layout.size());
+            Ok(new)
+        }
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        if is_aligned(ptr, new_layout.align()) {
+            // Shrinking in place is always possible; the tail is only
+            // reclaimed if this is the most recent allocation.
+            self.resize_last(ptr, old_layout.size(), new_layout.size());
+            return Ok(NonNull::slice_from_raw_parts(ptr, new_layout.size()));
+        }
+
+        let new = self.allocate(new_layout)?;
+        unsafe {
+            ptr::copy_nonoverlapping(ptr.as_ptr(), new.as_ptr() as *mut u8, new_layout.size());
+        }
+        Ok(new)
+    }
+}
+
+#[cfg(all(test, not(feature = "nightly")))]
+mod tests {
+    use super::*;
+    use allocator_api2::boxed::Box;
+    use allocator_api2::vec::Vec;
+
+    #[test]
+    fn test_vec_in_arena() {
+        let arena = Arena::new(4096).unwrap();
+        let mut v = Vec::new_in(&arena);
+        for i in 0..100u32 {
+            v.push(i);
+        }
+        assert_eq!(v.len(), 100);
+        assert_eq!(v[99], 99);
+        assert!(arena.used() >= 400);
+    }
+
+    #[test]
+    fn test_grow_in_place_at_top() {
+        let arena = Arena::new(4096).unwrap();
+        let mut v: Vec<u64, &Arena> = Vec::with_capacity_in(4, &arena);
+        v.extend(0..4);
+        let before = v.as_ptr();
+
+        v.reserve_exact(60);
+        assert_eq!(v.as_ptr(), before); // extended, not moved
+        assert_eq!(arena.used(), 64 * 8);
+    }
+
+    #[test]
+    fn test_grow_relocates_when_not_at_top() {
+        let arena = Arena::new(4096).unwrap();
+        let mut v: Vec<u64, &Arena> = Vec::with_capacity_in(4, &arena);
+        v.extend(0..4);
+        let before = v.as_ptr();
+        let _blocker = arena.alloc(0u8).unwrap();
+
+        v.reserve_exact(60);
+        assert_ne!(v.as_ptr(), before);
+        assert_eq!(v[..], [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_deallocate_last_releases() {
+        let arena = Arena::new(256).unwrap();
+        arena.alloc(1u64).unwrap();
+
+        let b = Box::new_in([7u64; 4], &arena);
+        assert_eq!(b[3], 7);
+        assert_eq!(arena.used(), 40);
+        drop(b);
+        assert_eq!(arena.used(), 8);
+
+        // Freeing anything but the top allocation is a no-op.
+        let first = Box::new_in(1u64, &arena);
+        let second = Box::new_in(2u64, &arena);
+        drop(first);
+        assert_eq!(arena.used(), 24);
+        drop(second);
+        assert_eq!(arena.used(), 16);
+    }
+
+    #[test]
+    fn test_shrink_at_top() {
+        let arena = Arena::new(256).unwrap();
+        let mut v: Vec<u8, &Arena> = Vec::with_capacity_in(100, &arena);
+        v.extend_from_slice(b"abc");
+        v.shrink_to_fit();
+        assert_eq!(arena.used(), 3);
+        assert_eq!(v[..], *b"abc");
+    }
+
+    #[test]
+    fn test_out_of_memory() {
+        let arena = Arena::new(16).unwrap();
+        let mut v: Vec<u64, &Arena> = Vec::new_in(&arena);
+        assert!(v.try_reserve(100).is_err());
+    }
+
+    #[test]
+    fn test_hashbrown_map_in_arena() {
+        let arena = Arena::builder(1024).growth(crate::Growth::Doubling).build().unwrap();
+        let mut map = hashbrown::HashMap::new_in(&arena);
+        for i in 0..1_000u32 {
+            map.insert(i, i * 2);
+        }
+        assert_eq!(map.len(), 1_000);
+        assert_eq!(map[&500], 1000);
+    }
+}
+
+#[cfg(all(test, feature = "nightly"))]
+mod nightly_tests {
+    use super::*;
+    use alloc::boxed::Box;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_std_collections_in_arena() {
+        let arena = Arena::new(4096).unwrap();
+        let mut v = Vec::new_in(&arena);
+        v.extend(0..100u32);
+        assert_eq!(v[99], 99);
+
+        let b = Box::new_in(5u64, &arena);
+        assert_eq!(*b, 5);
+    }
+}
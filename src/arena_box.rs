@@ -0,0 +1,313 @@
+use core::marker::PhantomData;
+use core::ptr::NonNull;
+
+/// An owning pointer to a value stored in an [`Arena`].
+///
+/// Unlike [`ArenaRef`], an `ArenaBox` runs the value's destructor when it is
+/// dropped, like `Box` does. The bytes themselves stay in the arena until it
+/// is reset; only `drop_in_place` is called. This makes it safe to put types
+/// that own resources, such as `Vec` or `String`, into a plain [`Arena`].
+///
+/// `T` may be unsized, e.g. `ArenaBox<'a, [T]>` from
+/// [`ArenaVec::into_boxed_slice`].
+///
+/// There is no `ArenaBox::into_pin`: an arbitrary box may be leaked with
+/// `mem::forget` and its memory reused by the next reset without the value
+/// being dropped, which `Pin` forbids. Use [`Arena::alloc_pinned`] instead.
+///
+/// # Example
+/// ```
+/// use arenars::Arena;
+///
+/// let arena = Arena::new(1024).unwrap();
+/// {
+///     let mut names = arena.alloc_box(vec!["a".to_string()]).unwrap();
+///     names.push("b".to_string());
+///     assert_eq!(names.len(), 2);
+/// } // the Vec and its Strings are dropped here
+/// ```
+///
+/// [`Arena`]: crate::Arena
+/// [`ArenaVec::into_boxed_slice`]: crate::ArenaVec::into_boxed_slice
+/// [`Arena::alloc_pinned`]: crate::Arena::alloc_pinned
+pub struct ArenaBox<'a, T: ?Sized> {
+    ptr: NonNull<T>,
+    // Set for boxes from `alloc_pinned`: how many values the arena still has
+    // to drop for this box, cleared once the box has dropped or given up the
+    // value.
+    drop_len: Option<NonNull<usize>>,
+    owns: PhantomData<T>,
+    arena: PhantomData<&'a ()>,
+}
+
+// SAFETY: an `ArenaBox` owns its `T` exactly like `Box<T>` does; the arena
+// only provides the bytes and never touches the value.
+unsafe impl<T: ?Sized + Send> Send for ArenaBox<'_, T> {}
+unsafe impl<T: ?Sized + Sync> Sync for ArenaBox<'_, T> {}
+
+impl<'a, T: ?Sized> ArenaBox<'a, T> {
+    /// Wrap a pointer to a value stored in an arena.
+    ///
+    /// # Safety
+    /// `ptr` must point to an initialized `T` in memory that stays valid for
+    /// `'a`, and nothing else may drop or hand out references to that value.
+    pub unsafe fn from_raw(ptr: NonNull<T>) -> Self {
+        unsafe { Self::from_parts(ptr, None) }
+    }
+
+    /// Like [`from_raw`](ArenaBox::from_raw), for a value that its arena
+    /// also drops unless `drop_len` is cleared first.
+    pub(crate) unsafe fn from_parts(ptr: NonNull<T>, drop_len: Option<NonNull<usize>>) -> Self {
+        Self { ptr, drop_len, owns: PhantomData, arena: PhantomData }
+    }
+
+    /// Take the box apart without touching its drop count.
+    pub(crate) fn into_parts(self) -> (NonNull<T>, Option<NonNull<usize>>) {
+        let parts = (self.ptr, self.drop_len);
+        core::mem::forget(self);
+        parts
+    }
+
+    /// Give up ownership without dropping the value, returning its pointer.
+    pub fn into_raw(self) -> NonNull<T> {
+        self.disarm();
+        self.into_parts().0
+    }
+
+    /// Stop the arena from dropping the value on reset.
+    fn disarm(&self) {
+        if let Some(len) = self.drop_len {
+            // The arena drops nothing for a count of zero, and only reads it
+            // through `&mut self`, which cannot happen while the box is live.
+            unsafe { len.as_ptr().write(0) }
+        }
+    }
+}
+
+impl<T> ArenaBox<'_, T> {
+    /// Move the value out of the arena.
+    pub fn into_inner(self) -> T {
+        let ptr = self.into_raw();
+        unsafe { ptr.as_ptr().read() }
+    }
+}
+
+impl<T: ?Sized> core::ops::Deref for ArenaBox<'_, T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T: ?Sized> core::ops::DerefMut for ArenaBox<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { self.ptr.as_mut() }
+    }
+}
+
+impl<T: ?Sized + core::fmt::Debug> core::fmt::Debug for ArenaBox<'_, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl<T: ?Sized> Drop for ArenaBox<'_, T> {
+    fn drop(&mut self) {
+        // Disarm first, so a panicking destructor is not run again on reset.
+        self.disarm();
+        unsafe { core::ptr::drop_in_place(self.ptr.as_ptr()) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Arena, ArenaRef, ArenaVec};
+    use alloc::format;
+    use alloc::string::{String, ToString};
+    use alloc::sync::Arc;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_drop_runs_destructor() {
+        let live = Arc::new(());
+        let arena = Arena::new(256).unwrap();
+
+        let boxed = arena.alloc_box(Arc::clone(&live)).unwrap();
+        assert_eq!(Arc::strong_count(&live), 2);
+        drop(boxed);
+        assert_eq!(Arc::strong_count(&live), 1);
+        assert_eq!(arena.used(), size_of::<Arc<()>>()); // bytes are not freed
+    }
+
+    #[test]
+    fn test_owning_types() {
+        let mut arena = Arena::new(256).unwrap();
+        {
+            let mut v = arena.alloc_box(Vec::new()).unwrap();
+            v.extend(["x".to_string(), "y".to_string()]);
+            assert_eq!(*v, ["x", "y"]);
+        }
+        // Nothing leaked, so the debug leak check stays quiet.
+        arena.reset();
+    }
+
+    #[test]
+    fn test_into_inner() {
+        let arena = Arena::new(64).unwrap();
+        let s: String = arena.alloc_box("moved".to_string()).unwrap().into_inner();
+        assert_eq!(s, "moved");
+    }
+
+    #[test]
+    fn test_ref_round_trip() {
+        let live = Arc::new(());
+        let mut arena = Arena::new(64).unwrap();
+
+        let r = arena.box_into_ref(arena.alloc_box(Arc::clone(&live)).unwrap());
+        assert_eq!(Arc::strong_count(&live), 2);
+
+        let boxed = unsafe { arena.box_from_ref(r) };
+        drop(boxed);
+        assert_eq!(Arc::strong_count(&live), 1);
+
+        // The value was handed back and dropped, so nothing is reported.
+        arena.reset();
+    }
+
+    #[test]
+    fn test_unsized_ref_round_trip() {
+        let live = Arc::new(());
+        let mut arena = Arena::new(256).unwrap();
+        {
+            let mut v = ArenaVec::new_in(&arena);
+            v.extend((0..2).map(|_| Arc::clone(&live))).unwrap();
+            let r: ArenaRef<'_, [Arc<()>]> = arena.box_into_ref(v.into_boxed_slice());
+            assert_eq!(Arc::strong_count(&live), 3);
+
+            let debug: ArenaBox<'_, dyn core::fmt::Debug> = crate::arena_unsize!(arena.alloc_box(Arc::clone(&live)).unwrap());
+            let debug = arena.box_into_ref(debug);
+            assert_eq!(format!("{:?}", &*debug), "()");
+
+            drop(unsafe { arena.box_from_ref(r) });
+            drop(unsafe { arena.box_from_ref(debug) });
+        }
+        assert_eq!(Arc::strong_count(&live), 1);
+        arena.reset();
+    }
+
+    #[test]
+    fn test_box_from_ref_clears_leak() {
+        let mut arena = Arena::new(64).unwrap();
+        let r = arena.alloc(String::from("owned")).unwrap();
+        drop(unsafe { arena.box_from_ref(r) });
+        arena.reset();
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[cfg_attr(miri, ignore)] // leaks on purpose
+    #[should_panic(expected = "alloc::string::String")]
+    fn test_box_into_ref_records_leak() {
+        let mut arena = Arena::new(64).unwrap();
+        arena.box_into_ref(arena.alloc_box(String::from("leaked")).unwrap());
+        arena.reset();
+    }
+
+    #[test]
+    fn test_unsized_slice() {
+        let live = Arc::new(());
+        let arena = Arena::new(256).unwrap();
+
+        let mut v = ArenaVec::new_in(&arena);
+        v.extend((0..3).map(|_| Arc::clone(&live))).unwrap();
+        let slice: ArenaBox<'_, [Arc<()>]> = v.into_boxed_slice();
+        assert_eq!(slice.len(), 3);
+        assert_eq!(Arc::strong_count(&live), 4);
+
+        drop(slice);
+        assert_eq!(Arc::strong_count(&live), 1);
+    }
+
+    #[test]
+    fn test_from_raw_str() {
+        let arena = Arena::new(64).unwrap();
+        let s = arena.alloc_str("text").unwrap();
+        let boxed: ArenaBox<'_, str> = unsafe { ArenaBox::from_raw(NonNull::from(s)) };
+        assert_eq!(&*boxed, "text");
+        assert_eq!(format!("{:?}", boxed), "\"text\"");
+    }
+
+    #[test]
+    fn test_pinned_dropped_once() {
+        let live = Arc::new(());
+        let mut arena = Arena::new(256).unwrap();
+
+        let pinned = arena.alloc_pinned(Arc::clone(&live)).unwrap();
+        drop(pinned);
+        assert_eq!(Arc::strong_count(&live), 1);
+
+        arena.reset(); // the disarmed entry drops nothing
+        assert_eq!(Arc::strong_count(&live), 1);
+    }
+
+    #[test]
+    fn test_forgotten_pinned_dropped_before_reuse() {
+        let live = Arc::new(());
+        let mut arena = Arena::new(256).unwrap();
+
+        core::mem::forget(arena.alloc_pinned(Arc::clone(&live)).unwrap());
+        assert_eq!(Arc::strong_count(&live), 2);
+        arena.reset();
+        assert_eq!(Arc::strong_count(&live), 1);
+
+        let mark = arena.checkpoint();
+        core::mem::forget(arena.alloc_pinned(Arc::clone(&live)).unwrap());
+        arena.rewind(mark).unwrap();
+        assert_eq!(Arc::strong_count(&live), 1);
+    }
+
+    #[test]
+    fn test_pinned_unpin_into_inner() {
+        let mut arena = Arena::new(256).unwrap();
+        let pinned = arena.alloc_pinned("moved out".to_string()).unwrap();
+        let s = core::pin::Pin::into_inner(pinned).into_inner();
+        arena.reset(); // not dropped a second time
+        assert_eq!(s, "moved out");
+    }
+
+    #[test]
+    fn test_pinned_self_reference() {
+        use core::marker::PhantomPinned;
+
+        // Checks on drop that it was never moved after being pinned.
+        struct Node {
+            this: *const Node,
+            _pin: PhantomPinned,
+        }
+
+        // SAFETY: `this` is only compared, never dereferenced.
+        unsafe impl Send for Node {}
+
+        impl Drop for Node {
+            fn drop(&mut self) {
+                assert_eq!(self.this, self as *const Node);
+            }
+        }
+
+        let arena = Arena::new(256).unwrap();
+        let mut node = arena.alloc_pinned(Node { this: core::ptr::null(), _pin: PhantomPinned }).unwrap();
+        let this = &*node as *const Node;
+        unsafe { node.as_mut().get_unchecked_mut().this = this };
+        drop(node);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_send_to_thread() {
+        let arena = Arena::new(64).unwrap();
+        let boxed = arena.alloc_box(alloc::vec![1u32, 2, 3]).unwrap();
+        let sum = std::thread::scope(|s| s.spawn(move || boxed.iter().sum::<u32>()).join().unwrap());
+        assert_eq!(sum, 6);
+    }
+}
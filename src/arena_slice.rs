@@ -0,0 +1,340 @@
+use core::ops::{Bound, RangeBounds};
+use core::ptr::NonNull;
+#[cfg(debug_assertions)]
+use core::sync::atomic::AtomicUsize;
+
+#[cfg(debug_assertions)]
+use alloc::sync::Arc;
+
+use crate::{Arena, ArenaBox};
+
+/// A slice of values allocated in an [`Arena`], returned by
+/// [`Arena::alloc_array`] and the other `alloc_slice_*` helpers.
+///
+/// `ArenaSlice` is to `[T]` what [`ArenaRef`] is to a single `T`: it derefs to
+/// `[T]` and its lifetime is tied to the arena. Like `ArenaRef`, its own
+/// operations are associated functions, so they never shadow slice methods:
+/// `s.split_at(1)` borrows, while `ArenaSlice::split_at(s, 1)` hands out two
+/// slices that live as long as the arena.
+///
+/// The elements are never dropped, like values stored with [`Arena::alloc`].
+/// For types that need `Drop`, [`into_boxed_slice`] switches to
+/// drop-on-release: the returned [`ArenaBox`] drops the elements when it
+/// goes away.
+///
+/// An `ArenaSlice` does not borrow the arena itself, so it can be sent to
+/// another thread like a `&mut [T]`.
+///
+/// # Example
+/// ```
+/// use arenars::{Arena, ArenaSlice};
+///
+/// let arena = Arena::new(1024).unwrap();
+/// let nums = arena.alloc_array(6, |i| i as u32).unwrap();
+/// let (evens, odds) = ArenaSlice::split_at(nums, 3);
+/// assert_eq!(evens, [0, 1, 2]);
+/// assert_eq!(ArenaSlice::slice(odds, 1..).iter().sum::<u32>(), 9);
+///
+/// let plain: &mut [u32] = ArenaSlice::leak(evens);
+/// plain[0] = 10;
+/// ```
+///
+/// [`ArenaRef`]: crate::ArenaRef
+/// [`into_boxed_slice`]: ArenaSlice::into_boxed_slice
+pub struct ArenaSlice<'arena, T> {
+    inner: &'arena mut [T],
+    // The arena's debug leak count for `T`, released by `into_boxed_slice`.
+    #[cfg(debug_assertions)]
+    leaked: Option<Arc<AtomicUsize>>,
+}
+
+impl<'arena, T> ArenaSlice<'arena, T> {
+    /// Wrap elements of `arena` that were recorded with `note_leak`.
+    #[cfg_attr(not(debug_assertions), allow(unused_variables))]
+    pub(crate) fn new(arena: &'arena Arena, inner: &'arena mut [T]) -> Self {
+        Self {
+            inner,
+            #[cfg(debug_assertions)]
+            leaked: arena.leak_counter::<T>(),
+        }
+    }
+
+    /// Wrap elements of an arena without a debug leak check, such as a
+    /// [`SyncArena`](crate::SyncArena).
+    pub(crate) fn untracked(inner: &'arena mut [T]) -> Self {
+        Self {
+            inner,
+            #[cfg(debug_assertions)]
+            leaked: None,
+        }
+    }
+
+    /// Wrap another part of the same elements.
+    fn part(&self, inner: &'arena mut [T]) -> Self {
+        Self {
+            inner,
+            #[cfg(debug_assertions)]
+            leaked: self.leaked.clone(),
+        }
+    }
+
+    /// Turn the slice into a plain `&'arena mut [T]`.
+    pub fn leak(this: Self) -> &'arena mut [T] {
+        this.inner
+    }
+
+    /// Split the slice in two at `mid`.
+    ///
+    /// # Panics
+    /// Panics if `mid > len`.
+    pub fn split_at(mut this: Self, mid: usize) -> (Self, Self) {
+        let (head, tail) = core::mem::take(&mut this.inner).split_at_mut(mid);
+        (this.part(head), this.part(tail))
+    }
+
+    /// Narrow the slice to `range`.
+    ///
+    /// # Panics
+    /// Panics if `range` is out of bounds, like indexing does.
+    pub fn slice<R: RangeBounds<usize>>(mut this: Self, range: R) -> Self {
+        let bounds: (Bound<usize>, Bound<usize>) = (range.start_bound().cloned(), range.end_bound().cloned());
+        this.inner = &mut core::mem::take(&mut this.inner)[bounds];
+        this
+    }
+
+    /// Hand the elements to an [`ArenaBox`], which drops them when it goes
+    /// away.
+    ///
+    /// ```
+    /// # use arenars::{Arena, ArenaSlice};
+    /// let mut arena = Arena::new(1024).unwrap();
+    /// let names = arena.alloc_array(3, |i| format!("{i}")).unwrap();
+    /// let names = ArenaSlice::into_boxed_slice(names);
+    /// assert_eq!(names[2], "2");
+    /// drop(names); // the Strings are freed here
+    /// arena.reset();
+    /// ```
+    pub fn into_boxed_slice(this: Self) -> ArenaBox<'arena, [T]> {
+        #[cfg(debug_assertions)]
+        if let Some(leaked) = &this.leaked {
+            crate::release_leak(leaked, this.inner.len());
+        }
+        // SAFETY: the elements were stored without a destructor and the
+        // `&'arena mut` guarantees nothing else refers to them.
+        unsafe { ArenaBox::from_raw(NonNull::from(this.inner)) }
+    }
+}
+
+impl<T> core::ops::Deref for ArenaSlice<'_, T> {
+    type Target = [T];
+    fn deref(&self) -> &[T] {
+        self.inner
+    }
+}
+
+impl<T> core::ops::DerefMut for ArenaSlice<'_, T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.inner
+    }
+}
+
+impl<'arena, T> IntoIterator for ArenaSlice<'arena, T> {
+    type Item = &'arena mut T;
+    type IntoIter = core::slice::IterMut<'arena, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.iter_mut()
+    }
+}
+
+impl<'s, T> IntoIterator for &'s ArenaSlice<'_, T> {
+    type Item = &'s T;
+    type IntoIter = core::slice::Iter<'s, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.iter()
+    }
+}
+
+impl<'s, T> IntoIterator for &'s mut ArenaSlice<'_, T> {
+    type Item = &'s mut T;
+    type IntoIter = core::slice::IterMut<'s, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.iter_mut()
+    }
+}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for ArenaSlice<'_, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<ArenaSlice<'_, U>> for ArenaSlice<'_, T> {
+    fn eq(&self, other: &ArenaSlice<'_, U>) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: Eq> Eq for ArenaSlice<'_, T> {}
+
+impl<T: PartialEq<U>, U> PartialEq<[U]> for ArenaSlice<'_, T> {
+    fn eq(&self, other: &[U]) -> bool {
+        **self == *other
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<&[U]> for ArenaSlice<'_, T> {
+    fn eq(&self, other: &&[U]) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize> PartialEq<[U; N]> for ArenaSlice<'_, T> {
+    fn eq(&self, other: &[U; N]) -> bool {
+        **self == *other
+    }
+}
+
+impl<T: core::hash::Hash> core::hash::Hash for ArenaSlice<'_, T> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+impl<T> AsRef<[T]> for ArenaSlice<'_, T> {
+    fn as_ref(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T> AsMut<[T]> for ArenaSlice<'_, T> {
+    fn as_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<T> core::borrow::Borrow<[T]> for ArenaSlice<'_, T> {
+    fn borrow(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T> core::borrow::BorrowMut<[T]> for ArenaSlice<'_, T> {
+    fn borrow_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<'arena, T> From<ArenaSlice<'arena, T>> for &'arena mut [T] {
+    fn from(value: ArenaSlice<'arena, T>) -> Self {
+        ArenaSlice::leak(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use alloc::string::String;
+    use alloc::sync::Arc;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_split_at_and_slice() {
+        let arena = Arena::new(256).unwrap();
+        let nums = arena.alloc_array(5, |i| i as u8).unwrap();
+        let (mut head, tail) = ArenaSlice::split_at(nums, 2);
+        head[0] = 9;
+        assert_eq!(head, [9, 1]);
+
+        let tail = ArenaSlice::slice(tail, 1..=1);
+        assert_eq!(tail, [3]);
+        assert_eq!(format!("{:?}", tail), "[3]");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_slice_out_of_bounds() {
+        let arena = Arena::new(64).unwrap();
+        let nums = arena.alloc_array(2, |i| i as u8).unwrap();
+        let _ = ArenaSlice::slice(nums, 1..3);
+    }
+
+    #[test]
+    fn test_iterate() {
+        let arena = Arena::new(256).unwrap();
+        let mut nums = arena.alloc_array(4, |i| i as u32).unwrap();
+        for n in &mut nums {
+            *n *= 10;
+        }
+        assert_eq!(nums.iter().sum::<u32>(), 60);
+
+        let refs: Vec<&mut u32> = nums.into_iter().collect();
+        assert_eq!(*refs[3], 30);
+    }
+
+    #[test]
+    fn test_leak() {
+        let arena = Arena::new(64).unwrap();
+        let slice: &mut [u16] = arena.alloc_array(3, |_| 1).unwrap().into();
+        slice[1] = 2;
+        assert_eq!(slice, [1, 2, 1]);
+    }
+
+    #[test]
+    fn test_into_boxed_slice_drops() {
+        let live = Arc::new(());
+        let mut arena = Arena::new(256).unwrap();
+
+        let shared = arena.alloc_array(3, |_| Arc::clone(&live)).unwrap();
+        let (first, rest) = ArenaSlice::split_at(shared, 1);
+        drop(ArenaSlice::into_boxed_slice(first));
+        drop(ArenaSlice::into_boxed_slice(rest));
+        assert_eq!(Arc::strong_count(&live), 1);
+
+        // Every element was handed back, so the debug leak check stays quiet.
+        arena.reset();
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[cfg_attr(miri, ignore)] // leaks on purpose
+    #[should_panic(expected = "leaked values of types that need Drop")]
+    fn test_sliced_off_elements_still_leak() {
+        let mut arena = Arena::new(256).unwrap();
+        let names = arena.alloc_array(3, |i| format!("{i}")).unwrap();
+        let boxed = ArenaSlice::into_boxed_slice(ArenaSlice::slice(names, ..2));
+        drop(boxed);
+        arena.reset();
+    }
+
+    #[test]
+    fn test_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<ArenaSlice<'_, String>>();
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_into_boxed_slice_on_another_thread() {
+        let mut arena = Arena::new(256).unwrap();
+        let names = arena.alloc_array(4, |i| format!("{i}")).unwrap();
+        let (head, tail) = ArenaSlice::split_at(names, 2);
+        std::thread::scope(|s| {
+            s.spawn(move || drop(ArenaSlice::into_boxed_slice(head)));
+            s.spawn(move || drop(ArenaSlice::into_boxed_slice(tail)));
+        });
+        // Both threads handed their elements back, so nothing is reported.
+        arena.reset();
+    }
+
+    #[test]
+    fn test_empty() {
+        let arena = Arena::new(64).unwrap();
+        let empty = arena.alloc_array(0, |_| String::new()).unwrap();
+        assert!(empty.is_empty());
+        assert_eq!(ArenaSlice::into_boxed_slice(empty).len(), 0);
+    }
+}
@@ -0,0 +1,280 @@
+use core::fmt;
+
+use crate::{Arena, ArenaError, ArenaVec};
+
+/// A growable UTF-8 string whose buffer lives inside an [`Arena`].
+///
+/// `ArenaString` is to [`ArenaVec<u8>`] what `String` is to `Vec<u8>`: it
+/// grows in place while it is the arena's most recent allocation and can be
+/// frozen into a `&'a mut str` with [`into_bump_str`]. It implements
+/// [`fmt::Write`], and the [`format_in!`] macro builds one directly from
+/// format arguments.
+///
+/// # Example
+/// ```
+/// use arenars::{format_in, Arena, ArenaString};
+///
+/// let arena = Arena::new(1024).unwrap();
+/// let mut s = ArenaString::new_in(&arena);
+/// s.push_str("hello").unwrap();
+/// s.push(',').unwrap();
+///
+/// let line = format_in!(&arena, "{s} {}!", "world").unwrap();
+/// assert_eq!(line, "hello, world!");
+/// ```
+///
+/// [`into_bump_str`]: ArenaString::into_bump_str
+/// [`format_in!`]: crate::format_in
+pub struct ArenaString<'a> {
+    bytes: ArenaVec<'a, u8>,
+}
+
+impl<'a> ArenaString<'a> {
+    /// Create an empty string in `arena`.
+    pub fn new_in(arena: &'a Arena) -> Self {
+        Self { bytes: ArenaVec::new_in(arena) }
+    }
+
+    /// Create an empty string in `arena` with room for `capacity` bytes.
+    pub fn with_capacity_in(capacity: usize, arena: &'a Arena) -> Result<Self, ArenaError> {
+        Ok(Self { bytes: ArenaVec::with_capacity_in(capacity, arena)? })
+    }
+
+    /// Create a string in `arena` from format arguments.
+    ///
+    /// This is what [`format_in!`](crate::format_in) expands to.
+    ///
+    /// # Panics
+    /// Panics if a `Display` or `Debug` implementation returns an error,
+    /// like `alloc::format!` does.
+    pub fn from_fmt_in(arena: &'a Arena, args: fmt::Arguments<'_>) -> Result<Self, ArenaError> {
+        // `fmt::Error` carries no information, so keep the arena's reason for
+        // failing on the side.
+        struct Adapter<'s, 'a> {
+            string: &'s mut ArenaString<'a>,
+            error: Option<ArenaError>,
+        }
+
+        impl fmt::Write for Adapter<'_, '_> {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                self.string.push_str(s).map_err(|e| {
+                    self.error = Some(e);
+                    fmt::Error
+                })
+            }
+        }
+
+        let mut string = Self::new_in(arena);
+        if let Some(s) = args.as_str() {
+            string.push_str(s)?;
+            return Ok(string);
+        }
+
+        let mut adapter = Adapter { string: &mut string, error: None };
+        match fmt::write(&mut adapter, args) {
+            Ok(()) => Ok(string),
+            Err(_) => Err(adapter.error
+                .expect("a formatting trait implementation returned an error")),
+        }
+    }
+
+    /// Length of the string in bytes.
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Returns `true` if the string is empty.
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Number of bytes the string can hold without growing.
+    pub fn capacity(&self) -> usize {
+        self.bytes.capacity()
+    }
+
+    /// Append `s` to the end of the string.
+    pub fn push_str(&mut self, s: &str) -> Result<(), ArenaError> {
+        self.bytes.extend_from_slice(s.as_bytes())
+    }
+
+    /// Append a single character.
+    pub fn push(&mut self, c: char) -> Result<(), ArenaError> {
+        self.push_str(c.encode_utf8(&mut [0; 4]))
+    }
+
+    /// Remove the last character and return it, or `None` if empty.
+    pub fn pop(&mut self) -> Option<char> {
+        let c = self.as_str().chars().next_back()?;
+        for _ in 0..c.len_utf8() {
+            self.bytes.pop();
+        }
+        Some(c)
+    }
+
+    /// Truncate the string to zero length, keeping the buffer.
+    pub fn clear(&mut self) {
+        self.bytes.clear();
+    }
+
+    /// View the contents as a `&str`.
+    pub fn as_str(&self) -> &str {
+        // Only whole `&str`s and `char`s are ever pushed.
+        unsafe { core::str::from_utf8_unchecked(&self.bytes) }
+    }
+
+    /// View the contents as a `&mut str`.
+    pub fn as_mut_str(&mut self) -> &mut str {
+        unsafe { core::str::from_utf8_unchecked_mut(&mut self.bytes) }
+    }
+
+    /// Freeze the string into a `&str` that lives as long as the arena.
+    ///
+    /// Unused capacity is handed back to the arena when the buffer is its
+    /// last allocation.
+    pub fn into_bump_str(self) -> &'a mut str {
+        unsafe { core::str::from_utf8_unchecked_mut(self.bytes.into_bump_slice()) }
+    }
+}
+
+impl core::ops::Deref for ArenaString<'_> {
+    type Target = str;
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl core::ops::DerefMut for ArenaString<'_> {
+    fn deref_mut(&mut self) -> &mut str {
+        self.as_mut_str()
+    }
+}
+
+impl fmt::Write for ArenaString<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s).map_err(|_| fmt::Error)
+    }
+}
+
+impl fmt::Display for ArenaString<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Debug for ArenaString<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl PartialEq<str> for ArenaString<'_> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for ArenaString<'_> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+/// Format into an [`ArenaString`] allocated in an arena.
+///
+/// Takes the arena followed by the usual `format!` arguments and returns
+/// `Result<ArenaString, ArenaError>`.
+///
+/// ```
+/// use arenars::{format_in, Arena};
+///
+/// let arena = Arena::new(256).unwrap();
+/// let id = format_in!(&arena, "node_{}", 42).unwrap();
+/// assert_eq!(id, "node_42");
+/// ```
+#[macro_export]
+macro_rules! format_in {
+    ($arena:expr, $($arg:tt)*) => {
+        $crate::ArenaString::from_fmt_in($arena, ::core::format_args!($($arg)*))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_push_str_and_push() {
+        let arena = Arena::new(256).unwrap();
+        let mut s = ArenaString::new_in(&arena);
+        s.push_str("grüß").unwrap();
+        s.push('!').unwrap();
+        s.push('é').unwrap();
+        assert_eq!(s, "grüß!é");
+        assert_eq!(s.pop(), Some('é'));
+        assert_eq!(s.pop(), Some('!'));
+        assert_eq!(s.len(), "grüß".len());
+    }
+
+    #[test]
+    fn test_write_fmt() {
+        let arena = Arena::new(256).unwrap();
+        let mut s = ArenaString::new_in(&arena);
+        let (name, n) = ("id", 7);
+        write!(s, "{name}-{n:03}").unwrap();
+        assert_eq!(s, "id-007");
+    }
+
+    #[test]
+    fn test_format_in() {
+        let arena = Arena::new(256).unwrap();
+        let (a, b) = (1, "two");
+        let s = format_in!(&arena, "{a} {b} {:?}", [3]).unwrap();
+        assert_eq!(s, "1 two [3]");
+        assert_eq!(format_in!(&arena, "literal").unwrap(), "literal");
+    }
+
+    #[test]
+    fn test_format_in_out_of_memory() {
+        let arena = Arena::new(8).unwrap();
+        let result = format_in!(&arena, "{}", "far too long for eight bytes");
+        assert!(matches!(result, Err(ArenaError::OutOfMemory { .. })));
+    }
+
+    #[test]
+    #[should_panic(expected = "formatting trait implementation returned an error")]
+    fn test_format_in_failing_display() {
+        struct Broken;
+
+        impl fmt::Display for Broken {
+            fn fmt(&self, _: &mut fmt::Formatter<'_>) -> fmt::Result {
+                Err(fmt::Error)
+            }
+        }
+
+        let arena = Arena::new(64).unwrap();
+        let _ = format_in!(&arena, "{}", Broken);
+    }
+
+    #[test]
+    fn test_into_bump_str() {
+        let arena = Arena::new(256).unwrap();
+        let mut s = ArenaString::with_capacity_in(64, &arena).unwrap();
+        s.push_str("frozen").unwrap();
+
+        let frozen: &mut str = s.into_bump_str();
+        frozen.make_ascii_uppercase();
+        assert_eq!(frozen, "FROZEN");
+        assert_eq!(arena.used(), 6);
+    }
+
+    #[test]
+    fn test_display_and_debug() {
+        let arena = Arena::new(64).unwrap();
+        let s = format_in!(&arena, "a\"b").unwrap();
+        assert_eq!(format!("{s}"), "a\"b");
+        assert_eq!(format!("{s:?}"), "\"a\\\"b\"");
+    }
+}
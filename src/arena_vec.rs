@@ -0,0 +1,405 @@
+use alloc::alloc::Layout;
+use core::ptr::{self, NonNull};
+
+use crate::{Arena, ArenaBox, ArenaError};
+
+/// A growable vector whose buffer lives inside an [`Arena`].
+///
+/// `ArenaVec` is for building sequences whose length is not known up front.
+/// When the buffer is the most recent allocation in the arena it grows in
+/// place; otherwise it is copied to a larger region of the arena and the old
+/// one is left behind until the arena is reset.
+///
+/// Dropping an `ArenaVec` drops its elements. [`into_bump_slice`] instead
+/// freezes it into a plain `&'a mut [T]` that lives as long as the arena.
+///
+/// # Example
+/// ```
+/// use arenars::{Arena, ArenaVec};
+///
+/// let arena = Arena::new(1024).unwrap();
+/// let mut v = ArenaVec::new_in(&arena);
+/// for i in 0..10u32 {
+///     v.push(i * i).unwrap();
+/// }
+/// v.insert(0, 100).unwrap();
+/// assert_eq!(v.pop(), Some(81));
+///
+/// let squares: &mut [u32] = v.into_bump_slice();
+/// assert_eq!(squares[..3], [100, 0, 1]);
+/// ```
+///
+/// [`into_bump_slice`]: ArenaVec::into_bump_slice
+pub struct ArenaVec<'a, T> {
+    arena: &'a Arena,
+    ptr: NonNull<T>,
+    len: usize,
+    cap: usize, // in number of T's, not bytes
+}
+
+impl<'a, T> ArenaVec<'a, T> {
+    /// Create an empty vector in `arena`. Nothing is allocated until the
+    /// first element is pushed.
+    pub fn new_in(arena: &'a Arena) -> Self {
+        Self {
+            arena,
+            ptr: NonNull::dangling(),
+            len: 0,
+            // Zero-sized elements never need a buffer.
+            cap: if size_of::<T>() == 0 { usize::MAX } else { 0 },
+        }
+    }
+
+    /// Create an empty vector in `arena` with room for `capacity` elements.
+    pub fn with_capacity_in(capacity: usize, arena: &'a Arena) -> Result<Self, ArenaError> {
+        let mut vec = Self::new_in(arena);
+        vec.reserve(capacity)?;
+        Ok(vec)
+    }
+
+    /// Number of elements in the vector.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the vector holds no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of elements the vector can hold without growing.
+    pub fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    /// Make room for at least `additional` more elements.
+    pub fn reserve(&mut self, additional: usize) -> Result<(), ArenaError> {
+        let required = self.len
+            .checked_add(additional)
+            .ok_or(ArenaError::SizeOverflow)?;
+        if required <= self.cap {
+            return Ok(());
+        }
+
+        let new_cap = required.max(self.cap.saturating_mul(2)).max(4);
+        self.grow_to(new_cap)
+    }
+
+    fn grow_to(&mut self, new_cap: usize) -> Result<(), ArenaError> {
+        let new_layout = Layout::array::<T>(new_cap)
+            .map_err(|_| ArenaError::SizeOverflow)?;
+
+        // Extend in place when the buffer is the arena's last allocation.
+        let old_size = self.cap * size_of::<T>();
+        if self.arena.resize_last(self.ptr.cast(), old_size, new_layout.size()) {
+            self.cap = new_cap;
+            return Ok(());
+        }
+
+        let new_ptr = self.arena.alloc_layout(new_layout)?.cast::<T>();
+        unsafe {
+            ptr::copy_nonoverlapping(self.ptr.as_ptr(), new_ptr.as_ptr(), self.len);
+        }
+        self.ptr = new_ptr;
+        self.cap = new_cap;
+        Ok(())
+    }
+
+    /// Append `value` to the end of the vector, growing it if needed.
+    pub fn push(&mut self, value: T) -> Result<(), ArenaError> {
+        if self.len == self.cap {
+            self.reserve(1)?;
+        }
+
+        unsafe {
+            self.ptr.as_ptr().add(self.len).write(value);
+        }
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Remove the last element and return it, or `None` if empty.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.len -= 1;
+        unsafe { Some(self.ptr.as_ptr().add(self.len).read()) }
+    }
+
+    /// Insert `value` at `index`, shifting later elements to the right.
+    ///
+    /// # Panics
+    /// Panics if `index > len`.
+    pub fn insert(&mut self, index: usize, value: T) -> Result<(), ArenaError> {
+        assert!(index <= self.len, "insertion index (is {index}) should be <= len (is {})", self.len);
+
+        if self.len == self.cap {
+            self.reserve(1)?;
+        }
+
+        unsafe {
+            let slot = self.ptr.as_ptr().add(index);
+            ptr::copy(slot, slot.add(1), self.len - index);
+            slot.write(value);
+        }
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Append every item of `iter`, reserving space up front from its
+    /// `size_hint`.
+    pub fn extend<I>(&mut self, iter: I) -> Result<(), ArenaError>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0)?;
+        for value in iter {
+            self.push(value)?;
+        }
+        Ok(())
+    }
+
+    /// Append a copy of every element of `values`.
+    pub fn extend_from_slice(&mut self, values: &[T]) -> Result<(), ArenaError>
+    where
+        T: Copy,
+    {
+        self.reserve(values.len())?;
+        unsafe {
+            let end = self.ptr.as_ptr().add(self.len);
+            ptr::copy_nonoverlapping(values.as_ptr(), end, values.len());
+        }
+        self.len += values.len();
+        Ok(())
+    }
+
+    /// Drop every element, keeping the buffer.
+    pub fn clear(&mut self) {
+        let len = self.len;
+        // Set the length first so a panicking destructor cannot cause a
+        // double drop.
+        self.len = 0;
+        unsafe {
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.ptr.as_ptr(), len));
+        }
+    }
+
+    /// Freeze the vector into a slice that lives as long as the arena.
+    ///
+    /// Unused capacity is handed back to the arena when the buffer is its
+    /// last allocation. Like values stored with [`Arena::alloc`], the
+    /// elements are never dropped.
+    pub fn into_bump_slice(self) -> &'a mut [T] {
+        self.arena.note_leak::<T>(self.len);
+        unsafe { &mut *self.into_raw_slice().as_ptr() }
+    }
+
+    /// Convert the vector into an [`ArenaBox`] that drops the elements when
+    /// it is dropped.
+    ///
+    /// Unused capacity is handed back to the arena when the buffer is its
+    /// last allocation.
+    pub fn into_boxed_slice(self) -> ArenaBox<'a, [T]> {
+        unsafe { ArenaBox::from_raw(self.into_raw_slice()) }
+    }
+
+    /// Release unused capacity and give up ownership of the elements.
+    fn into_raw_slice(self) -> NonNull<[T]> {
+        let this = core::mem::ManuallyDrop::new(self);
+        if this.cap > this.len && size_of::<T>() > 0 {
+            let size = size_of::<T>();
+            this.arena.resize_last(this.ptr.cast(), this.cap * size, this.len * size);
+        }
+        NonNull::slice_from_raw_parts(this.ptr, this.len)
+    }
+}
+
+impl<T> core::ops::Deref for ArenaVec<'_, T> {
+    type Target = [T];
+    fn deref(&self) -> &[T] {
+        unsafe { core::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T> core::ops::DerefMut for ArenaVec<'_, T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { core::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for ArenaVec<'_, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl<T> Drop for ArenaVec<'_, T> {
+    fn drop(&mut self) {
+        self.clear();
+        // Give the buffer back if nothing was allocated after it.
+        if size_of::<T>() > 0 {
+            self.arena.resize_last(self.ptr.cast(), self.cap * size_of::<T>(), 0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Growth;
+    use alloc::format;
+    use alloc::string::{String, ToString};
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_push_pop() {
+        let arena = Arena::new(1024).unwrap();
+        let mut v = ArenaVec::new_in(&arena);
+        assert!(v.is_empty());
+
+        for i in 0..20u32 {
+            v.push(i).unwrap();
+        }
+        assert_eq!(v.len(), 20);
+        assert_eq!(v.pop(), Some(19));
+        assert_eq!(v[..3], [0, 1, 2]);
+    }
+
+    #[test]
+    fn test_grows_in_place_at_top() {
+        let arena = Arena::new(1024).unwrap();
+        let mut v = ArenaVec::with_capacity_in(4, &arena).unwrap();
+        v.extend(0..4u64).unwrap();
+        let before = v.as_ptr();
+
+        v.push(4).unwrap();
+        assert_eq!(v.as_ptr(), before);
+        assert_eq!(arena.used(), v.capacity() * 8);
+    }
+
+    #[test]
+    fn test_relocates_when_not_at_top() {
+        let arena = Arena::new(1024).unwrap();
+        let mut v = ArenaVec::with_capacity_in(4, &arena).unwrap();
+        v.extend(0..4u64).unwrap();
+        let before = v.as_ptr();
+        arena.alloc(0u8).unwrap();
+
+        v.push(4).unwrap();
+        assert_ne!(v.as_ptr(), before);
+        assert_eq!(v[..], [0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_grows_into_new_chunk() {
+        let arena = Arena::builder(64).growth(Growth::Doubling).build().unwrap();
+        let mut v = ArenaVec::new_in(&arena);
+        v.extend(0..1_000u32).unwrap();
+        assert_eq!(v.iter().sum::<u32>(), 499_500);
+        assert!(arena.chunk_count() > 1);
+    }
+
+    #[test]
+    fn test_insert() {
+        let arena = Arena::new(1024).unwrap();
+        let mut v = ArenaVec::new_in(&arena);
+        v.extend([1, 2, 4]).unwrap();
+        v.insert(2, 3).unwrap();
+        v.insert(0, 0).unwrap();
+        v.insert(5, 5).unwrap();
+        assert_eq!(v[..], [0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    #[should_panic(expected = "insertion index")]
+    fn test_insert_out_of_bounds() {
+        let arena = Arena::new(64).unwrap();
+        let mut v = ArenaVec::new_in(&arena);
+        v.insert(1, 0u8).unwrap();
+    }
+
+    #[test]
+    fn test_extend_from_slice() {
+        let arena = Arena::new(1024).unwrap();
+        let mut v = ArenaVec::new_in(&arena);
+        v.push(0u16).unwrap();
+        v.extend_from_slice(&[1, 2, 3]).unwrap();
+        v.extend_from_slice(&[]).unwrap();
+        assert_eq!(v[..], [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_into_bump_slice_returns_unused_capacity() {
+        let arena = Arena::new(1024).unwrap();
+        let mut v = ArenaVec::with_capacity_in(32, &arena).unwrap();
+        v.extend(0..3u32).unwrap();
+
+        let slice = v.into_bump_slice();
+        assert_eq!(slice, [0, 1, 2]);
+        assert_eq!(arena.used(), 12);
+    }
+
+    #[test]
+    fn test_drop_runs_destructors_and_frees_top() {
+        static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+        struct Guard;
+
+        impl Drop for Guard {
+            fn drop(&mut self) {
+                DROPS.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let arena = Arena::new(1024).unwrap();
+        arena.alloc(1u64).unwrap();
+        {
+            let mut v = ArenaVec::new_in(&arena);
+            v.push((Guard, 0u64)).unwrap();
+            v.push((Guard, 1u64)).unwrap();
+        }
+        assert_eq!(DROPS.load(Ordering::SeqCst), 2);
+        assert_eq!(arena.used(), 8);
+    }
+
+    #[test]
+    fn test_owned_elements() {
+        let arena = Arena::new(1024).unwrap();
+        let mut v: ArenaVec<'_, String> = ArenaVec::new_in(&arena);
+        v.extend((0..5).map(|i| i.to_string())).unwrap();
+        assert_eq!(v.pop().as_deref(), Some("4"));
+        v.clear();
+        assert!(v.is_empty());
+    }
+
+    #[test]
+    fn test_zero_sized_elements() {
+        let arena = Arena::new(8).unwrap();
+        let mut v = ArenaVec::new_in(&arena);
+        for _ in 0..1_000 {
+            v.push(()).unwrap();
+        }
+        assert_eq!(v.len(), 1_000);
+        assert_eq!(arena.used(), 0);
+    }
+
+    #[test]
+    fn test_out_of_memory() {
+        let arena = Arena::new(16).unwrap();
+        let mut v = ArenaVec::new_in(&arena);
+        v.extend(0..4u32).unwrap();
+        assert!(matches!(v.extend(0..4u32), Err(ArenaError::OutOfMemory { .. })));
+        assert_eq!(v.len(), 4);
+    }
+
+    #[test]
+    fn test_debug_format() {
+        let arena = Arena::new(64).unwrap();
+        let mut v = ArenaVec::new_in(&arena);
+        v.extend([1u8, 2]).unwrap();
+        assert_eq!(format!("{:?}", v), "[1, 2]");
+    }
+}
@@ -1,407 +1,3057 @@
 #![no_std]
+#![cfg_attr(feature = "nightly", feature(allocator_api, coerce_unsized, pin_coerce_unsized_trait, unsize))]
 
 extern crate alloc;
 
 #[cfg(feature = "std")]
 extern crate std;
 
-use alloc::alloc::{self as allocator, Layout};
-use core::ptr::NonNull;
-
+use alloc::alloc::{self as allocator, GlobalAlloc, Layout};
+#[cfg(debug_assertions)]
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::cell::{Cell, RefCell};
+use core::convert::Infallible;
+use core::pin::Pin;
+use core::ptr::{self, NonNull};
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+#[cfg(any(feature = "allocator-api2", feature = "nightly"))]
+mod allocator_api;
+pub mod arena_box;
+pub mod arena_slice;
+pub mod arena_string;
+pub mod arena_vec;
+pub mod sync_arena;
 pub mod typed_arena;
+#[doc(hidden)]
+pub mod unsize;
+pub use arena_box::ArenaBox;
+pub use arena_slice::ArenaSlice;
+pub use arena_string::ArenaString;
+pub use arena_vec::ArenaVec;
+pub use sync_arena::SyncArena;
 pub use typed_arena::TypedArena;
 
-pub struct Arena {
+/// Default alignment of the chunk buffers allocated by an [`Arena`].
+const CHUNK_ALIGN: usize = 8;
+
+/// How an [`Arena`] behaves once its current chunk is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Growth {
+    /// Never grow: allocations that do not fit in the initial buffer fail
+    /// with [`ArenaError::OutOfMemory`], or go to the fallback allocator if
+    /// one is configured. This is what [`Arena::new`] uses.
+    Fixed,
+    /// Allocate a new chunk the same size as the initial one.
+    Linear,
+    /// Allocate a new chunk twice the size of the previous one.
+    Doubling,
+}
+
+/// Builder for an [`Arena`] with a non-default configuration.
+///
+/// ```
+/// # use arenars::{Arena, Growth};
+/// let arena = Arena::builder(64).growth(Growth::Doubling).build().unwrap();
+/// let big = arena.alloc_array(100, |i| i as u64).unwrap();
+/// assert_eq!(big.len(), 100);
+/// assert!(arena.capacity() > 64);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ArenaBuilder {
+    config: Config,
+}
+
+/// Settings shared by an arena and the scratch arenas it hands out.
+#[derive(Debug, Clone)]
+struct Config {
+    chunk_size: usize,
+    align: usize,
+    growth: Growth,
+    retain_chunks: bool,
+    name: Option<&'static str>,
+    fallback: Option<Fallback>,
+}
+
+/// Allocator used for requests that do not fit in an arena built with
+/// [`ArenaBuilder::heap_fallback`] or [`ArenaBuilder::fallback_allocator`].
+#[derive(Clone, Copy)]
+enum Fallback {
+    Global,
+    Custom(&'static (dyn GlobalAlloc + Sync)),
+}
+
+impl Fallback {
+    unsafe fn alloc(self, layout: Layout) -> *mut u8 {
+        unsafe {
+            match self {
+                Fallback::Global => allocator::alloc(layout),
+                Fallback::Custom(a) => a.alloc(layout),
+            }
+        }
+    }
+
+    unsafe fn dealloc(self, ptr: *mut u8, layout: Layout) {
+        unsafe {
+            match self {
+                Fallback::Global => allocator::dealloc(ptr, layout),
+                Fallback::Custom(a) => a.dealloc(ptr, layout),
+            }
+        }
+    }
+}
+
+impl core::fmt::Debug for Fallback {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Fallback::Global => write!(f, "Global"),
+            Fallback::Custom(_) => write!(f, "Custom"),
+        }
+    }
+}
+
+/// An allocation served by the fallback allocator, freed on reset.
+struct Spill {
+    ptr: NonNull<u8>,
+    layout: Layout,
+    serial: usize, // the arena's checkpoint serial when it was made
+}
+
+/// How much an arena has spilled to its fallback allocator, returned by
+/// [`Arena::spill_stats`].
+///
+/// A non-zero `peak_bytes` means the arena was too small for the workload;
+/// adding it to the capacity would have avoided every spill.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SpillStats {
+    /// Allocations served by the fallback allocator since the arena was
+    /// created.
+    pub allocations: usize,
+    /// Bytes served by the fallback allocator since the arena was created.
+    pub bytes: usize,
+    /// Spilled bytes currently live, released by the next reset.
+    pub live_bytes: usize,
+    /// Largest `live_bytes` seen since the arena was created.
+    pub peak_bytes: usize,
+}
+
+impl ArenaBuilder {
+    /// Set the growth policy used when the current chunk is full.
+    pub fn growth(mut self, growth: Growth) -> Self {
+        self.config.growth = growth;
+        self
+    }
+
+    /// Whether [`Arena::reset`] keeps the extra chunks allocated by growth
+    /// (`true`, the default) or frees everything but the initial chunk.
+    pub fn retain_chunks(mut self, retain: bool) -> Self {
+        self.config.retain_chunks = retain;
+        self
+    }
+
+    /// Align every chunk buffer to `align` bytes (default 8), e.g. to a cache
+    /// line or SIMD register width. Must be a power of two.
+    ///
+    /// Allocations are always aligned for their own type regardless of this
+    /// setting; it only controls where each chunk starts.
+    pub fn alignment(mut self, align: usize) -> Self {
+        self.config.align = align;
+        self
+    }
+
+    /// Name the arena. The name is included in [`ArenaError::OutOfMemory`]
+    /// so failures can be traced back to the arena that ran out.
+    pub fn name(mut self, name: &'static str) -> Self {
+        self.config.name = Some(name);
+        self
+    }
+
+    /// Serve allocations that do not fit from the global allocator instead of
+    /// failing with [`ArenaError::OutOfMemory`]. Only [`Growth::Fixed`]
+    /// arenas run out; growing arenas add a chunk instead.
+    ///
+    /// Spilled allocations are tracked and freed on [`Arena::reset`],
+    /// [`Arena::rewind`] past them, or when the arena is dropped.
+    /// [`Arena::spill_stats`] reports how much was spilled, to help size the
+    /// arena.
+    pub fn heap_fallback(mut self, enabled: bool) -> Self {
+        self.config.fallback = enabled.then_some(Fallback::Global);
+        self
+    }
+
+    /// Like [`heap_fallback`](ArenaBuilder::heap_fallback), but spill to
+    /// `allocator` instead of the global allocator.
+    pub fn fallback_allocator(mut self, allocator: &'static (dyn GlobalAlloc + Sync)) -> Self {
+        self.config.fallback = Some(Fallback::Custom(allocator));
+        self
+    }
+
+    /// Allocate the initial chunk and build the arena.
+    pub fn build(self) -> Result<Arena, ArenaError> {
+        if self.config.chunk_size == 0 {
+            return Err(ArenaError::InvalidSize);
+        }
+
+        let chunk = Chunk::new(self.config.chunk_size, self.config.align)?;
+        Ok(Arena::from_chunk(chunk, self.config))
+    }
+}
+
+/// A single buffer used by an [`Arena`].
+///
+/// Chunks are normally owned heap allocations. The first chunk of a scratch
+/// arena created by [`Arena::scope`] borrows the tail of its parent's chunk
+/// instead and is not freed on drop.
+struct Chunk {
     memory: NonNull<u8>,
     size: usize,
+    align: usize,
+    owned: bool,
+}
+
+impl Chunk {
+    fn new(size: usize, align: usize) -> Result<Self, ArenaError> {
+        if !align.is_power_of_two() {
+            return Err(ArenaError::InvalidAlignment { align });
+        }
+        let layout = Layout::from_size_align(size, align)
+            .map_err(|_| ArenaError::SizeOverflow)?;
+
+        let memory = unsafe {
+            let ptr = allocator::alloc(layout);
+            if ptr.is_null() {
+                return Err(ArenaError::AllocationFailed { size, align });
+            }
+            NonNull::new_unchecked(ptr)
+        };
+
+        Ok(Chunk { memory, size, align, owned: true })
+    }
+}
+
+impl Drop for Chunk {
+    fn drop(&mut self) {
+        if !self.owned {
+            return;
+        }
+        unsafe {
+            let layout = Layout::from_size_align_unchecked(self.size, self.align);
+            allocator::dealloc(self.memory.as_ptr(), layout);
+        }
+    }
+}
+
+/// A non-null pointer aligned to `layout.align()`, for zero-sized allocations.
+pub(crate) fn dangling(layout: Layout) -> NonNull<u8> {
+    unsafe { NonNull::new_unchecked(ptr::without_provenance_mut(layout.align())) }
+}
+
+/// Offsets `(start, end)` of `layout` placed at the first suitably aligned
+/// address at or after `base + offset`, or `None` if the arithmetic overflows.
+pub(crate) fn aligned_range(base: usize, offset: usize, layout: Layout) -> Option<(usize, usize)> {
+    let align = layout.align();
+    let addr = base.checked_add(offset)?.checked_add(align - 1)? & !(align - 1);
+    let start = addr - base;
+    let end = start.checked_add(layout.size())?;
+    Some((start, end))
+}
+
+/// Drop glue for a value registered with [`Arena::alloc_with_drop`].
+///
+/// Entries are allocated inside the arena next to the values they drop and
+/// form a singly linked list, newest first, so they are run in reverse
+/// allocation order.
+struct DropEntry {
+    prev: Option<NonNull<DropEntry>>,
+    value: NonNull<u8>,
+    len: usize,
+    drop_fn: unsafe fn(NonNull<u8>, usize),
+    serial: usize, // the arena's checkpoint serial when it was registered
+}
+
+/// Whether `T` is sized, i.e. pointers to it are thin.
+const fn is_sized<T: ?Sized>() -> bool {
+    size_of::<*const T>() == size_of::<*const ()>()
+}
+
+/// Drop `len` consecutive `T`s starting at `ptr`.
+unsafe fn drop_glue<T>(ptr: NonNull<u8>, len: usize) {
+    unsafe {
+        ptr::drop_in_place(ptr::slice_from_raw_parts_mut(ptr.as_ptr() as *mut T, len));
+    }
+}
+
+/// A bump allocator over one or more heap chunks.
+///
+/// By default ([`Arena::new`]) the arena is a single fixed-size buffer. Use
+/// [`Arena::builder`] with a [`Growth`] policy to let it allocate further
+/// chunks when the current one is full. Chunks are never moved or freed while
+/// the arena is borrowed, so references stay valid across growth.
+///
+/// Allocation only needs `&self`: the bump state lives in [`Cell`]s, so any
+/// number of [`ArenaRef`]s and slices can be live at the same time. Methods
+/// that invalidate memory, such as [`Arena::reset`], take `&mut self`.
+///
+/// Values stored with [`alloc`](Arena::alloc) are never dropped. Use
+/// [`alloc_with_drop`](Arena::alloc_with_drop) for types that own resources;
+/// their destructors run in reverse allocation order on `reset`, `rewind` and
+/// when the arena is dropped.
+///
+/// ```
+/// # use arenars::Arena;
+/// let arena = Arena::new(64).unwrap();
+/// let a = arena.alloc(1u32).unwrap();
+/// let b = arena.alloc(2u32).unwrap();
+/// assert_eq!(*a + *b, 3);
+/// ```
+pub struct Arena {
+    chunks: RefCell<Vec<Chunk>>,
+    base: Cell<NonNull<u8>>, // start of the current chunk
+    limit: Cell<usize>,      // size of the current chunk
+    current: Cell<usize>,    // index of the chunk being bumped
+    offset: Cell<usize>,     // bump offset within the current chunk
+    retired: Cell<usize>,    // size of the chunks before `current`, unused tails included
+    capacity: Cell<usize>,   // total bytes across all chunks
+    drops: Cell<Option<NonNull<DropEntry>>>, // newest registered destructor
+    spills: RefCell<Vec<Spill>>, // live allocations from the fallback allocator
+    spill_stats: Cell<SpillStats>,
+    id: usize,             // tells this arena's checkpoints from others'
+    resets: usize,         // number of resets, which invalidate checkpoints
+    serial: Cell<usize>,   // bumped by each checkpoint; stamps drop entries and spills
+    #[cfg(debug_assertions)]
+    leaks: RefCell<Vec<(&'static str, Arc<AtomicUsize>)>>, // values that need Drop stored by `alloc`, per type
+    config: Config,
+}
+
+/// Take `count` values off a debug leak counter. Saturates: a reference
+/// handed to `box_from_ref` may point into a larger value that was recorded
+/// under another type.
+#[cfg(debug_assertions)]
+pub(crate) fn release_leak(leaked: &AtomicUsize, count: usize) {
+    let _ = leaked.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| Some(n.saturating_sub(count)));
+}
+
+/// Source of arena ids, so that a [`Checkpoint`] from one arena never
+/// matches another, even one that reuses its memory.
+static NEXT_ARENA: AtomicUsize = AtomicUsize::new(1);
+
+/// A saved bump position, returned by [`Arena::checkpoint`].
+///
+/// Pass it to [`Arena::rewind`] to release everything allocated after the
+/// checkpoint was taken. Taking one is just a copy of the bump state, and
+/// marks from another arena, from before a [`reset`], or past the current
+/// bump position are rejected.
+///
+/// [`reset`]: Arena::reset
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint {
+    arena: usize,
+    resets: usize,
+    serial: usize,
+    index: usize,
     offset: usize,
+    retired: usize,
 }
 
+// SAFETY: an `Arena` exclusively owns its chunks and spilled allocations
+// (the fallback allocator is `Sync`), and the values stored in them are only
+// reachable through borrows of the arena, which cannot be live while the
+// arena is moved to another thread. The arena never reads those values
+// itself, so moving the bytes of a `!Send` value is harmless. The only values
+// it drops are those registered by `alloc_with_drop`, `alloc_array_with_drop`
+// and `alloc_pinned`, which require `T: Send + 'static`.
+//
+// `Arena` is deliberately not `Sync`: allocating through `&Arena` updates
+// the bump offset without synchronization. Use `SyncArena` for that.
+unsafe impl Send for Arena {}
+
 /// A reference to a value allocated in an [`Arena`].
 ///
 /// The lifetime `'arena` is tied to the arena that owns the backing memory,
 /// so the borrow checker statically prevents:
 /// - using the reference after the arena is dropped
 /// - calling [`Arena::reset`] while any `ArenaRef` is still live
-pub struct ArenaRef<'arena, T> {
+pub struct ArenaRef<'arena, T: ?Sized> {
     inner: &'arena mut T,
 }
 
-impl<'arena, T> core::ops::Deref for ArenaRef<'arena, T> {
+impl<'arena, T: ?Sized> core::ops::Deref for ArenaRef<'arena, T> {
     type Target = T;
     fn deref(&self) -> &T {
         self.inner
     }
 }
 
-impl<'arena, T> core::ops::DerefMut for ArenaRef<'arena, T> {
+impl<'arena, T: ?Sized> core::ops::DerefMut for ArenaRef<'arena, T> {
     fn deref_mut(&mut self) -> &mut T {
         self.inner
     }
 }
 
-impl<'arena, T: core::fmt::Debug> core::fmt::Debug for ArenaRef<'arena, T> {
+impl<'arena, T: ?Sized + core::fmt::Debug> core::fmt::Debug for ArenaRef<'arena, T> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         self.inner.fmt(f)
     }
 }
 
-impl Arena {
-    /// Create a new arena with the specified size in bytes
-    pub fn new(size: usize) -> Result<Self, ArenaError> {
-        if size == 0 {
-            return Err(ArenaError::InvalidSize);
-        }
+impl<'arena, T: ?Sized> ArenaRef<'arena, T> {
+    /// Turn the reference into a plain `&'arena mut T`.
+    ///
+    /// This is an associated function rather than a method so that it does
+    /// not shadow methods of `T`; call it as `ArenaRef::leak(r)`.
+    pub fn leak(this: Self) -> &'arena mut T {
+        this.inner
+    }
 
-        let layout = Layout::from_size_align(size, 8)
-            .map_err(|_| ArenaError::InvalidAlignment)?;
+    /// Make a new `ArenaRef` for a part of the value, such as a field.
+    ///
+    /// # Example
+    /// ```
+    /// use arenars::{Arena, ArenaRef};
+    ///
+    /// struct Node { id: u32, weight: f32 }
+    ///
+    /// let arena = Arena::new(64).unwrap();
+    /// let node = arena.alloc(Node { id: 7, weight: 0.5 }).unwrap();
+    /// let id: ArenaRef<'_, u32> = ArenaRef::map(node, |n| &mut n.id);
+    /// assert_eq!(*id, 7);
+    /// ```
+    pub fn map<U: ?Sized, F>(this: Self, f: F) -> ArenaRef<'arena, U>
+    where
+        F: FnOnce(&mut T) -> &mut U,
+    {
+        ArenaRef { inner: f(this.inner) }
+    }
 
-        let memory = unsafe {
-            let ptr = allocator::alloc(layout);
-            if ptr.is_null() {
-                return Err(ArenaError::AllocationFailed);
-            }
-            NonNull::new_unchecked(ptr)
-        };
+    /// Split the reference into two `ArenaRef`s for disjoint parts of the
+    /// value.
+    pub fn map_split<U: ?Sized, V: ?Sized, F>(this: Self, f: F) -> (ArenaRef<'arena, U>, ArenaRef<'arena, V>)
+    where
+        F: FnOnce(&mut T) -> (&mut U, &mut V),
+    {
+        let (a, b) = f(this.inner);
+        (ArenaRef { inner: a }, ArenaRef { inner: b })
+    }
 
-        Ok(Arena {
-            memory,
-            size,
-            offset: 0,
-        })
+    /// Turn a pinned `ArenaRef` into a pinned `&'arena mut T`.
+    pub fn leak_pinned(this: Pin<Self>) -> Pin<&'arena mut T> {
+        // SAFETY: the value stays pinned; only the pointer type changes.
+        unsafe { Pin::new_unchecked(Pin::into_inner_unchecked(this).inner) }
     }
 
-    /// Allocate space for a single object of type T, initialized with `value`.
+    /// Project a pinned `ArenaRef` into a part of the value, keeping the
+    /// `'arena` lifetime. This is [`Pin::map_unchecked_mut`] for `ArenaRef`.
     ///
-    /// Returns an [`ArenaRef`] whose lifetime is bound to the arena, so the
-    /// borrow checker prevents both use-after-drop and calling [`reset`] while
-    /// the reference is live.
-    pub fn alloc<T>(&mut self, value: T) -> Result<ArenaRef<'_, T>, ArenaError> {
-        let ptr = self.alloc_layout(Layout::new::<T>())?;
+    /// # Safety
+    /// Same as [`Pin::map_unchecked_mut`]: the returned part must not move as
+    /// long as the value does not, and `f` must not move out of the value.
+    pub unsafe fn map_pinned<U: ?Sized, F>(this: Pin<Self>, f: F) -> Pin<ArenaRef<'arena, U>>
+    where
+        F: FnOnce(&mut T) -> &mut U,
+    {
+        unsafe { Pin::new_unchecked(Self::map(Pin::into_inner_unchecked(this), f)) }
+    }
+}
 
-        unsafe {
-            let typed_ptr = ptr.as_ptr() as *mut T;
-            typed_ptr.write(value);
-            Ok(ArenaRef { inner: &mut *typed_ptr })
+impl<'arena, A, B> ArenaRef<'arena, (A, B)> {
+    /// Split a reference to a pair into references to its elements.
+    ///
+    /// Use [`ArenaRef::map_split`] for other tuples and structs.
+    pub fn split(this: Self) -> (ArenaRef<'arena, A>, ArenaRef<'arena, B>) {
+        Self::map_split(this, |(a, b)| (a, b))
+    }
+}
+
+impl<'arena, T> ArenaRef<'arena, [T]> {
+    /// Split a slice reference in two at `mid`.
+    ///
+    /// # Panics
+    /// Panics if `mid > len`.
+    pub fn split_at(this: Self, mid: usize) -> (ArenaRef<'arena, [T]>, ArenaRef<'arena, [T]>) {
+        Self::map_split(this, |s| s.split_at_mut(mid))
+    }
+}
+
+impl<T: ?Sized + PartialEq> PartialEq for ArenaRef<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: ?Sized + Eq> Eq for ArenaRef<'_, T> {}
+
+impl<T: ?Sized + PartialOrd> PartialOrd for ArenaRef<'_, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        (**self).partial_cmp(&**other)
+    }
+}
+
+impl<T: ?Sized + Ord> Ord for ArenaRef<'_, T> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        (**self).cmp(&**other)
+    }
+}
+
+impl<T: ?Sized + core::hash::Hash> core::hash::Hash for ArenaRef<'_, T> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+impl<T: ?Sized + core::fmt::Display> core::fmt::Display for ArenaRef<'_, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl<T: ?Sized> AsRef<T> for ArenaRef<'_, T> {
+    fn as_ref(&self) -> &T {
+        self
+    }
+}
+
+impl<T: ?Sized> AsMut<T> for ArenaRef<'_, T> {
+    fn as_mut(&mut self) -> &mut T {
+        self
+    }
+}
+
+impl<T: ?Sized> core::borrow::Borrow<T> for ArenaRef<'_, T> {
+    fn borrow(&self) -> &T {
+        self
+    }
+}
+
+impl<T: ?Sized> core::borrow::BorrowMut<T> for ArenaRef<'_, T> {
+    fn borrow_mut(&mut self) -> &mut T {
+        self
+    }
+}
+
+impl Arena {
+    /// Create a new fixed-size arena with the specified size in bytes.
+    pub fn new(size: usize) -> Result<Self, ArenaError> {
+        Self::builder(size).build()
+    }
+
+    /// Create a new fixed-size arena whose buffer starts at an address that
+    /// is a multiple of `align`, which must be a power of two.
+    ///
+    /// ```
+    /// # use arenars::Arena;
+    /// let arena = Arena::with_alignment(4096, 64).unwrap();
+    /// let first = arena.alloc(0u8).unwrap();
+    /// assert_eq!(&*first as *const u8 as usize % 64, 0);
+    /// ```
+    pub fn with_alignment(size: usize, align: usize) -> Result<Self, ArenaError> {
+        Self::builder(size).alignment(align).build()
+    }
+
+    /// Start configuring an arena whose initial chunk is `size` bytes.
+    pub fn builder(size: usize) -> ArenaBuilder {
+        ArenaBuilder {
+            config: Config {
+                chunk_size: size,
+                align: CHUNK_ALIGN,
+                growth: Growth::Fixed,
+                retain_chunks: true,
+                name: None,
+                fallback: None,
+            },
         }
     }
 
-    /// Allocate space for an array of `count` elements, each initialized by
-    /// calling `init(index)`.
+    fn from_chunk(chunk: Chunk, config: Config) -> Self {
+        Arena {
+            base: Cell::new(chunk.memory),
+            limit: Cell::new(chunk.size),
+            capacity: Cell::new(chunk.size),
+            chunks: RefCell::new(alloc::vec![chunk]),
+            current: Cell::new(0),
+            offset: Cell::new(0),
+            retired: Cell::new(0),
+            drops: Cell::new(None),
+            spills: RefCell::new(Vec::new()),
+            spill_stats: Cell::new(SpillStats::default()),
+            id: NEXT_ARENA.fetch_add(1, Ordering::Relaxed),
+            resets: 0,
+            serial: Cell::new(1),
+            #[cfg(debug_assertions)]
+            leaks: RefCell::new(Vec::new()),
+            config,
+        }
+    }
+
+    /// Allocate a single `T` whose destructor runs when the arena is reset,
+    /// rewound past it, or dropped.
     ///
-    /// Using a closure lets callers initialize elements with their index (or any
-    /// other logic) without requiring `T: Clone` or `T: Default`.
+    /// This lets types that own resources (`String`, `Vec<T>`, ...) live next
+    /// to plain data in one arena. Destructors run in reverse allocation
+    /// order. For types that do not need dropping this is the same as
+    /// [`alloc`](Arena::alloc); otherwise a small drop entry is stored in the
+    /// arena alongside the value.
+    ///
+    /// `T: Send` is required because the arena may be sent to another thread
+    /// and run the destructor there, and `T: 'static` because nothing ties
+    /// the value to the arena's lifetime: the destructor may run after
+    /// anything the value borrowed is gone.
     ///
     /// ```
     /// # use arenars::Arena;
-    /// let mut arena = Arena::new(256).unwrap();
-    /// let squares = arena.alloc_array(4, |i| (i * i) as u32).unwrap();
-    /// assert_eq!(squares, [0, 1, 4, 9]);
+    /// let mut arena = Arena::new(1024).unwrap();
+    /// let name = arena.alloc_with_drop(String::from("node")).unwrap();
+    /// let id = arena.alloc(7u32).unwrap();
+    /// assert_eq!((name.as_str(), *id), ("node", 7));
+    ///
+    /// arena.reset(); // the String is dropped here — no leak
+    /// ```
+    ///
+    /// Values that borrow something shorter-lived are rejected:
+    ///
+    /// ```compile_fail
+    /// # use arenars::Arena;
+    /// struct Reader<'a>(&'a str);
+    ///
+    /// impl Drop for Reader<'_> {
+    ///     fn drop(&mut self) {
+    ///         println!("{}", self.0);
+    ///     }
+    /// }
+    ///
+    /// let arena = Arena::new(1024).unwrap();
+    /// {
+    ///     let s = String::from("gone");
+    ///     arena.alloc_with_drop(Reader(&s)).unwrap();
+    /// }
+    /// drop(arena); // would read `s` after it was freed
     /// ```
-    pub fn alloc_array<T, F>(&mut self, count: usize, mut init: F) -> Result<&mut [T], ArenaError>
+    pub fn alloc_with_drop<T: Send + 'static>(&self, value: T) -> Result<ArenaRef<'_, T>, ArenaError> {
+        if !core::mem::needs_drop::<T>() {
+            return self.alloc(value);
+        }
+
+        let (ptr, entry) = self.alloc_with_entry::<T>()?;
+
+        unsafe {
+            ptr.as_ptr().write(value);
+            self.register_drop(entry, ptr.cast(), 1, drop_glue::<T>);
+            Ok(ArenaRef { inner: &mut *ptr.as_ptr() })
+        }
+    }
+
+    /// Reserve a `T` and the `DropEntry` right after it in one allocation, so
+    /// that running out of space wastes neither.
+    fn alloc_with_entry<T>(&self) -> Result<(NonNull<T>, NonNull<u8>), ArenaError> {
+        let (layout, entry_offset) = Layout::new::<T>()
+            .extend(Layout::new::<DropEntry>())
+            .map_err(|_| ArenaError::SizeOverflow)?;
+        let ptr = self.alloc_layout(layout)?;
+        Ok((ptr.cast(), unsafe { NonNull::new_unchecked(ptr.as_ptr().add(entry_offset)) }))
+    }
+
+    /// Allocate an array of `count` elements, each initialized by calling
+    /// `init(index)`, whose destructors run when the arena is reset, rewound
+    /// past it, or dropped.
+    ///
+    /// See [`alloc_with_drop`](Arena::alloc_with_drop).
+    ///
+    /// Unlike [`alloc_array`](Arena::alloc_array), this returns a plain
+    /// `&mut [T]` rather than an [`ArenaSlice`]: the arena already drops the
+    /// elements, so they must not be handed to
+    /// [`ArenaSlice::into_boxed_slice`] as well.
+    #[allow(clippy::mut_from_ref)] // each call hands out a fresh, disjoint region
+    pub fn alloc_array_with_drop<T, F>(&self, count: usize, mut init: F) -> Result<&mut [T], ArenaError>
     where
+        T: Send + 'static,
         F: FnMut(usize) -> T,
     {
-        if count == 0 {
-            return Ok(&mut []);
+        if count == 0 || !core::mem::needs_drop::<T>() {
+            return self.alloc_array(count, init).map(ArenaSlice::leak);
         }
 
-        let layout = Layout::array::<T>(count)
-            .map_err(|_| ArenaError::InvalidSize)?;
+        // The drop entry goes right after the elements, so a failed
+        // initialization can give back both in one go.
+        let (layout, entry_offset) = Layout::array::<T>(count)
+            .and_then(|array| array.extend(Layout::new::<DropEntry>()))
+            .map_err(|_| ArenaError::SizeOverflow)?;
         let ptr = self.alloc_layout(layout)?;
 
         unsafe {
-            let base = ptr.as_ptr() as *mut T;
-            // Initialize each slot individually before we hand out the slice.
-            for i in 0..count {
-                base.add(i).write(init(i));
+            let Ok(slice) = self.init_array(ptr, layout.size(), count, |i| Ok::<_, Infallible>(init(i)));
+            // Registered only once every element is initialized.
+            let entry = NonNull::new_unchecked(ptr.as_ptr().add(entry_offset));
+            self.register_drop(entry, ptr, count, drop_glue::<T>);
+            Ok(slice)
+        }
+    }
+
+    /// Link a drop entry, written to `entry`, for `len` values at `value`.
+    ///
+    /// # Safety
+    /// `entry` must be a fresh arena allocation for a `DropEntry`, and `value`
+    /// must hold `len` initialized values that `drop_fn` may drop once.
+    unsafe fn register_drop(
+        &self,
+        entry: NonNull<u8>,
+        value: NonNull<u8>,
+        len: usize,
+        drop_fn: unsafe fn(NonNull<u8>, usize),
+    ) {
+        let entry = entry.cast::<DropEntry>();
+        unsafe {
+            entry.as_ptr().write(DropEntry {
+                prev: self.drops.get(),
+                value,
+                len,
+                drop_fn,
+                serial: self.serial.get(),
+            });
+        }
+        self.drops.set(Some(entry));
+    }
+
+    /// Remember that `count` `T`s were stored without registering their
+    /// destructor.
+    #[inline]
+    #[cfg_attr(not(debug_assertions), allow(unused_variables, clippy::extra_unused_type_parameters))]
+    fn note_leak<T: ?Sized>(&self, count: usize) {
+        #[cfg(debug_assertions)]
+        if core::mem::needs_drop::<T>() && count > 0 {
+            let name = core::any::type_name::<T>();
+            let mut leaks = self.leaks.borrow_mut();
+            match leaks.iter().find(|(n, _)| *n == name) {
+                Some((_, leaked)) => {
+                    leaked.fetch_add(count, Ordering::Relaxed);
+                }
+                None => leaks.push((name, Arc::new(AtomicUsize::new(count)))),
             }
-            Ok(core::slice::from_raw_parts_mut(base, count))
         }
     }
 
-    /// Allocate space for a single object of type T without initializing it.
+    /// The counter [`note_leak`](Arena::note_leak) keeps for `T`, for an
+    /// [`ArenaSlice`] to release without going through the arena.
+    #[cfg(debug_assertions)]
+    pub(crate) fn leak_counter<T: ?Sized>(&self) -> Option<Arc<AtomicUsize>> {
+        if !core::mem::needs_drop::<T>() {
+            return None;
+        }
+        let name = core::any::type_name::<T>();
+        let leaks = self.leaks.borrow();
+        leaks.iter().find(|(n, _)| *n == name).map(|(_, leaked)| Arc::clone(leaked))
+    }
+
+    /// Undo [`note_leak`](Arena::note_leak) for `count` `T`s that something
+    /// else has taken over dropping.
+    #[inline]
+    #[cfg_attr(not(debug_assertions), allow(unused_variables, clippy::extra_unused_type_parameters))]
+    fn forget_leak<T: ?Sized>(&self, count: usize) {
+        #[cfg(debug_assertions)]
+        if count > 0 && let Some(leaked) = self.leak_counter::<T>() {
+            release_leak(&leaked, count);
+        }
+    }
+
+    /// Panic in debug builds if values that need `Drop` were leaked since the
+    /// last check.
+    fn check_leaks(&self) {
+        #[cfg(debug_assertions)]
+        {
+            // Counters stay listed at zero while an `ArenaSlice` may still
+            // hold them, so skip those.
+            let leaks: Vec<_> = core::mem::take(&mut *self.leaks.borrow_mut())
+                .into_iter()
+                .filter(|(_, leaked)| leaked.load(Ordering::Relaxed) > 0)
+                .map(|(name, _)| name)
+                .collect();
+            assert!(
+                leaks.is_empty(),
+                "Arena leaked values of types that need Drop: {:?}; \
+                 allocate them with `alloc_with_drop` or a `TypedArena` instead",
+                leaks
+            );
+        }
+    }
+
+    /// Run the destructors registered after checkpoint serial `keep`, newest
+    /// first.
+    fn run_drops(&mut self, keep: usize) {
+        while let Some(entry) = self.drops.get() {
+            unsafe {
+                let DropEntry { prev, value, len, drop_fn, serial } = entry.as_ptr().read();
+                if serial <= keep {
+                    break;
+                }
+                // Unlink first so a panicking destructor is never run twice.
+                self.drops.set(prev);
+                drop_fn(value, len);
+            }
+        }
+    }
+
+    /// Allocate space for a single object of type T, initialized with `value`.
     ///
-    /// Returns an [`ArenaRef`] wrapping `MaybeUninit<T>`. The caller **must**
-    /// initialize the value before calling `.assume_init_mut()` or reading
-    /// through the reference.
+    /// Returns an [`ArenaRef`] whose lifetime is bound to the arena, so the
+    /// borrow checker prevents both use-after-drop and calling [`reset`] while
+    /// the reference is live.
     ///
-    /// Prefer [`alloc`] unless you have a specific performance reason to skip
-    /// initialization.
-    pub fn alloc_uninit<T>(&mut self) -> Result<ArenaRef<'_, core::mem::MaybeUninit<T>>, ArenaError> {
-        let ptr = self.alloc_layout(Layout::new::<T>())?;
+    /// The value is never dropped. In debug builds, storing a type that needs
+    /// `Drop` this way is recorded and reported by a panic on the next
+    /// [`reset`](Arena::reset); use [`alloc_with_drop`](Arena::alloc_with_drop)
+    /// or a [`TypedArena`] for such types.
+    pub fn alloc<T>(&self, value: T) -> Result<ArenaRef<'_, T>, ArenaError> {
+        self.try_alloc(value).map_err(|e| e.error)
+    }
+
+    /// Like [`alloc`](Arena::alloc), but hands `value` back inside the error
+    /// if the allocation fails, so it can be stored somewhere else.
+    ///
+    /// ```
+    /// # use arenars::Arena;
+    /// let arena = Arena::new(8).unwrap();
+    /// arena.alloc(0u64).unwrap();
+    ///
+    /// let name = String::from("expensive");
+    /// let name = match arena.try_alloc(name) {
+    ///     Ok(_) => unreachable!("the arena is full"),
+    ///     Err(e) => Box::new(e.into_inner()), // fall back to the heap
+    /// };
+    /// assert_eq!(*name, "expensive");
+    /// ```
+    pub fn try_alloc<T>(&self, value: T) -> Result<ArenaRef<'_, T>, AllocError<T>> {
+        let ptr = match self.alloc_layout(Layout::new::<T>()) {
+            Ok(ptr) => ptr,
+            Err(error) => return Err(AllocError { error, value }),
+        };
+        self.note_leak::<T>(1);
+
+        unsafe {
+            let typed_ptr = ptr.as_ptr() as *mut T;
+            typed_ptr.write(value);
+            Ok(ArenaRef { inner: &mut *typed_ptr })
+        }
+    }
+
+    /// Allocate a single `T` owned by the returned [`ArenaBox`], which drops
+    /// it when the box goes away.
+    ///
+    /// Only the destructor runs early; the bytes are reclaimed by the next
+    /// [`reset`](Arena::reset) like any other allocation.
+    ///
+    /// ```
+    /// # use arenars::Arena;
+    /// let mut arena = Arena::new(1024).unwrap();
+    /// let list = arena.alloc_box(vec![1, 2, 3]).unwrap();
+    /// assert_eq!(list.len(), 3);
+    /// drop(list); // the Vec's heap buffer is freed here
+    /// arena.reset();
+    /// ```
+    pub fn alloc_box<T>(&self, value: T) -> Result<ArenaBox<'_, T>, ArenaError> {
+        let ptr = self.alloc_layout(Layout::new::<T>())?.cast::<T>();
+
+        unsafe {
+            ptr.as_ptr().write(value);
+            Ok(ArenaBox::from_raw(ptr))
+        }
+    }
+
+    /// Turn an [`ArenaBox`] from this arena into an [`ArenaRef`]. The value
+    /// will no longer be dropped, like values stored with
+    /// [`alloc`](Arena::alloc), and is reported by the same debug leak check.
+    ///
+    /// `T` may be unsized, e.g. a slice or trait object; such values are not
+    /// counted by the leak check, as their element type is not known here.
+    ///
+    /// ```
+    /// # use arenars::{Arena, ArenaRef, ArenaVec};
+    /// let arena = Arena::new(1024).unwrap();
+    /// let mut v = ArenaVec::new_in(&arena);
+    /// v.extend([1u32, 2, 3]).unwrap();
+    /// let nums: ArenaRef<'_, [u32]> = arena.box_into_ref(v.into_boxed_slice());
+    /// assert_eq!(*nums, [1, 2, 3]);
+    /// ```
+    pub fn box_into_ref<'a, T: ?Sized>(&'a self, value: ArenaBox<'a, T>) -> ArenaRef<'a, T> {
+        if is_sized::<T>() {
+            self.note_leak::<T>(1);
+        }
+        let ptr = value.into_raw();
+        ArenaRef { inner: unsafe { &mut *ptr.as_ptr() } }
+    }
+
+    /// Take ownership of a value in this arena behind an [`ArenaRef`], so
+    /// that it is dropped along with the returned box.
+    ///
+    /// # Safety
+    /// The value must not be dropped by anything else. In particular, it must
+    /// not come from [`alloc_with_drop`](Arena::alloc_with_drop), whose
+    /// values the arena drops itself.
+    pub unsafe fn box_from_ref<'a, T: ?Sized>(&'a self, value: ArenaRef<'a, T>) -> ArenaBox<'a, T> {
+        if is_sized::<T>() {
+            self.forget_leak::<T>(1);
+        }
+        unsafe { ArenaBox::from_raw(NonNull::from(value.inner)) }
+    }
+
+    /// Allocate a `T` that stays pinned in place, owned by the returned
+    /// [`ArenaBox`].
+    ///
+    /// Arena memory never moves, and the value is guaranteed to be dropped
+    /// before its memory is reused: normally when the box goes away, or, if
+    /// the box is leaked with `mem::forget`, by the next reset, a rewind past
+    /// it, or dropping the arena. That makes the arena a home for futures and
+    /// intrusive list nodes. As with [`alloc_with_drop`](Arena::alloc_with_drop),
+    /// a small drop entry is stored alongside values that need `Drop`, and
+    /// `T: Send + 'static` is required because the arena may drop them on
+    /// another thread, after anything they borrowed is gone.
+    ///
+    /// ```compile_fail
+    /// # use arenars::Arena;
+    /// let arena = Arena::new(1024).unwrap();
+    /// {
+    ///     let s = String::from("gone");
+    ///     core::mem::forget(arena.alloc_pinned(async { s.len() }).unwrap());
+    /// }
+    /// drop(arena); // would drop the future after `s` was freed
+    /// ```
+    ///
+    /// ```
+    /// # use arenars::Arena;
+    /// use core::task::{Context, Poll, Waker};
+    ///
+    /// let arena = Arena::new(1024).unwrap();
+    /// let mut task = arena.alloc_pinned(async { 40 + 2 }).unwrap();
+    ///
+    /// let mut cx = Context::from_waker(Waker::noop());
+    /// assert_eq!(task.as_mut().poll(&mut cx), Poll::Ready(42));
+    /// ```
+    pub fn alloc_pinned<T: Send + 'static>(&self, value: T) -> Result<Pin<ArenaBox<'_, T>>, ArenaError> {
+        let (ptr, entry) = if core::mem::needs_drop::<T>() {
+            let (ptr, entry) = self.alloc_with_entry::<T>()?;
+            (ptr, Some(entry))
+        } else {
+            (self.alloc_layout(Layout::new::<T>())?.cast::<T>(), None)
+        };
+
+        unsafe {
+            ptr.as_ptr().write(value);
+            if let Some(entry) = entry {
+                self.register_drop(entry, ptr.cast(), 1, drop_glue::<T>);
+            }
+            // SAFETY: the value never moves, and either the box or the arena
+            // drops it before the memory can be reused.
+            let drop_len = entry.map(|entry| NonNull::new_unchecked(&raw mut (*entry.cast::<DropEntry>().as_ptr()).len));
+            Ok(Pin::new_unchecked(ArenaBox::from_parts(ptr, drop_len)))
+        }
+    }
+
+    /// Allocate a single `T` built by `f`, writing it straight into the arena.
+    ///
+    /// The slot is reserved before `f` runs, so the compiler can construct
+    /// the value in place instead of building it on the stack and copying it
+    /// in, which matters for large types. If `f` panics, the slot is given
+    /// back.
+    ///
+    /// ```
+    /// # use arenars::Arena;
+    /// let arena = Arena::new(64 * 1024).unwrap();
+    /// let table = arena.alloc_with(|| [0u64; 4096]).unwrap();
+    /// assert_eq!(table.len(), 4096);
+    /// ```
+    ///
+    /// Like [`alloc`](Arena::alloc), the value is never dropped.
+    #[inline(always)]
+    pub fn alloc_with<T, F>(&self, f: F) -> Result<ArenaRef<'_, T>, ArenaError>
+    where
+        F: FnOnce() -> T,
+    {
+        let layout = Layout::new::<T>();
+        let ptr = self.alloc_layout(layout)?;
+
+        unsafe {
+            let typed_ptr = ptr.as_ptr() as *mut T;
+            let guard = InitGuard { arena: self, base: typed_ptr, size: layout.size(), len: 0 };
+            typed_ptr.write(f());
+            core::mem::forget(guard);
+            self.note_leak::<T>(1);
+            Ok(ArenaRef { inner: &mut *typed_ptr })
+        }
+    }
+
+    /// Allocate space for an array of `count` elements, each initialized by
+    /// calling `init(index)`.
+    ///
+    /// Using a closure lets callers initialize elements with their index (or any
+    /// other logic) without requiring `T: Clone` or `T: Default`.
+    ///
+    /// ```
+    /// # use arenars::Arena;
+    /// let arena = Arena::new(256).unwrap();
+    /// let squares = arena.alloc_array(4, |i| (i * i) as u32).unwrap();
+    /// assert_eq!(squares, [0, 1, 4, 9]);
+    /// ```
+    ///
+    /// Like [`alloc`](Arena::alloc), the elements are never dropped; see
+    /// [`ArenaSlice::into_boxed_slice`] for types that need `Drop`.
+    pub fn alloc_array<T, F>(&self, count: usize, mut init: F) -> Result<ArenaSlice<'_, T>, ArenaError>
+    where
+        F: FnMut(usize) -> T,
+    {
+        if count == 0 {
+            return Ok(ArenaSlice::new(self, &mut []));
+        }
+
+        let layout = Layout::array::<T>(count)
+            .map_err(|_| ArenaError::SizeOverflow)?;
+        let ptr = self.alloc_layout(layout)?;
+
+        let Ok(slice) = unsafe {
+            self.init_array(ptr, layout.size(), count, |i| Ok::<_, Infallible>(init(i)))
+        };
+        self.note_leak::<T>(count);
+        Ok(ArenaSlice::new(self, slice))
+    }
+
+    /// Allocate an array of `count` elements, each initialized by the
+    /// fallible `init(index)`.
+    ///
+    /// If `init` returns an error, the elements written so far are dropped,
+    /// the space is given back to the arena, and the error is returned as
+    /// [`AllocOrInitError::Init`].
+    ///
+    /// ```
+    /// # use arenars::{AllocOrInitError, Arena};
+    /// let arena = Arena::new(256).unwrap();
+    /// let parsed = arena.try_alloc_array(3, |i| ["1", "2", "x"][i].parse::<u32>());
+    /// assert!(matches!(parsed, Err(AllocOrInitError::Init(_))));
+    /// assert_eq!(arena.used(), 0);
+    /// ```
+    ///
+    /// Like [`alloc`](Arena::alloc), the elements are never dropped once the
+    /// array is returned.
+    pub fn try_alloc_array<T, E, F>(&self, count: usize, init: F) -> Result<ArenaSlice<'_, T>, AllocOrInitError<E>>
+    where
+        F: FnMut(usize) -> Result<T, E>,
+    {
+        if count == 0 {
+            return Ok(ArenaSlice::new(self, &mut []));
+        }
+
+        let layout = Layout::array::<T>(count)
+            .map_err(|_| ArenaError::SizeOverflow)?;
+        let ptr = self.alloc_layout(layout)?;
+
+        let slice = unsafe { self.init_array(ptr, layout.size(), count, init) }
+            .map_err(AllocOrInitError::Init)?;
+        self.note_leak::<T>(count);
+        Ok(ArenaSlice::new(self, slice))
+    }
+
+    /// Allocate a single `T` produced by the fallible `f`.
+    ///
+    /// The slot is reserved before `f` runs. If `f` returns an error or
+    /// panics, the slot is given back to the arena.
+    pub fn try_alloc_with<T, E, F>(&self, f: F) -> Result<ArenaRef<'_, T>, AllocOrInitError<E>>
+    where
+        F: FnOnce() -> Result<T, E>,
+    {
+        let layout = Layout::new::<T>();
+        let ptr = self.alloc_layout(layout)?;
+
+        let mut f = Some(f);
+        let slice = unsafe { self.init_array(ptr, layout.size(), 1, |_| (f.take().unwrap())()) }
+            .map_err(AllocOrInitError::Init)?;
+        self.note_leak::<T>(1);
+        Ok(ArenaRef { inner: &mut slice[0] })
+    }
+
+    /// Write `count` values produced by `init` into the fresh `size`-byte
+    /// allocation at `ptr`.
+    ///
+    /// If `init` fails or panics, the values written so far are dropped and
+    /// the allocation is given back, provided nothing was allocated after it
+    /// in the meantime.
+    ///
+    /// # Safety
+    /// `ptr` must be a fresh arena allocation of `size` bytes with room for
+    /// `count` `T`s.
+    #[allow(clippy::mut_from_ref)]
+    unsafe fn init_array<T, E, F>(&self, ptr: NonNull<u8>, size: usize, count: usize, mut init: F) -> Result<&mut [T], E>
+    where
+        F: FnMut(usize) -> Result<T, E>,
+    {
+        let base = ptr.as_ptr() as *mut T;
+        let mut guard = InitGuard { arena: self, base, size, len: 0 };
+
+        // Initialize each slot individually before we hand out the slice.
+        while guard.len < count {
+            let value = init(guard.len)?;
+            unsafe { base.add(guard.len).write(value) };
+            guard.len += 1;
+        }
+
+        core::mem::forget(guard);
+        unsafe { Ok(core::slice::from_raw_parts_mut(base, count)) }
+    }
+
+    /// Allocate space for a single object of type T without initializing it.
+    ///
+    /// Returns an [`ArenaRef`] wrapping `MaybeUninit<T>`. The caller **must**
+    /// initialize the value before calling `.assume_init_mut()` or reading
+    /// through the reference.
+    ///
+    /// Prefer [`alloc`] unless you have a specific performance reason to skip
+    /// initialization.
+    pub fn alloc_uninit<T>(&self) -> Result<ArenaRef<'_, core::mem::MaybeUninit<T>>, ArenaError> {
+        let ptr = self.alloc_layout(Layout::new::<T>())?;
+
+        unsafe {
+            let typed_ptr = ptr.as_ptr() as *mut core::mem::MaybeUninit<T>;
+            Ok(ArenaRef { inner: &mut *typed_ptr })
+        }
+    }
+
+    /// Allocate space for an array of `count` elements without initializing them.
+    ///
+    /// Returns an [`ArenaSlice`] of `MaybeUninit<T>`. The caller **must**
+    /// initialize every element before reading from the slice.
+    ///
+    /// Prefer [`alloc_array`] unless you have a specific performance reason to
+    /// skip initialization.
+    pub fn alloc_array_uninit<T>(
+        &self,
+        count: usize,
+    ) -> Result<ArenaSlice<'_, core::mem::MaybeUninit<T>>, ArenaError> {
+        if count == 0 {
+            return Ok(ArenaSlice::new(self, &mut []));
+        }
+
+        let layout = Layout::array::<T>(count)
+            .map_err(|_| ArenaError::SizeOverflow)?;
+        let ptr = self.alloc_layout(layout)?;
+
+        unsafe {
+            let base = ptr.as_ptr() as *mut core::mem::MaybeUninit<T>;
+            Ok(ArenaSlice::new(self, core::slice::from_raw_parts_mut(base, count)))
+        }
+    }
+
+    /// Copy `s` into the arena.
+    ///
+    /// ```
+    /// # use arenars::Arena;
+    /// let arena = Arena::new(64).unwrap();
+    /// let name = arena.alloc_str("ident").unwrap();
+    /// name.make_ascii_uppercase();
+    /// assert_eq!(name, "IDENT");
+    /// ```
+    ///
+    /// [`ArenaSlice`] only wraps `[T]`, so this returns a plain `&mut str`.
+    #[allow(clippy::mut_from_ref)] // each call hands out a fresh, disjoint region
+    pub fn alloc_str(&self, s: &str) -> Result<&mut str, ArenaError> {
+        let layout = Layout::array::<u8>(s.len())
+            .map_err(|_| ArenaError::SizeOverflow)?;
+        let ptr = self.alloc_layout(layout)?;
+
+        unsafe {
+            ptr::copy_nonoverlapping(s.as_ptr(), ptr.as_ptr(), s.len());
+            let bytes = core::slice::from_raw_parts_mut(ptr.as_ptr(), s.len());
+            Ok(core::str::from_utf8_unchecked_mut(bytes))
+        }
+    }
+
+    /// Copy the elements of `src` into the arena with a single `memcpy`.
+    pub fn alloc_slice_copy<T: Copy>(&self, src: &[T]) -> Result<ArenaSlice<'_, T>, ArenaError> {
+        let layout = Layout::for_value(src);
+        let ptr = self.alloc_layout(layout)?;
+
+        unsafe {
+            let base = ptr.as_ptr() as *mut T;
+            ptr::copy_nonoverlapping(src.as_ptr(), base, src.len());
+            Ok(ArenaSlice::new(self, core::slice::from_raw_parts_mut(base, src.len())))
+        }
+    }
+
+    /// Clone the elements of `src` into the arena.
+    ///
+    /// Like [`alloc`](Arena::alloc), the clones are never dropped.
+    pub fn alloc_slice_clone<T: Clone>(&self, src: &[T]) -> Result<ArenaSlice<'_, T>, ArenaError> {
+        self.alloc_array(src.len(), |i| src[i].clone())
+    }
+
+    /// Allocate a slice of `len` elements, each produced by `f(index)`.
+    ///
+    /// The same as [`alloc_array`](Arena::alloc_array), named to sit alongside
+    /// the other `alloc_slice_*` helpers.
+    pub fn alloc_slice_fill_with<T, F>(&self, len: usize, f: F) -> Result<ArenaSlice<'_, T>, ArenaError>
+    where
+        F: FnMut(usize) -> T,
+    {
+        self.alloc_array(len, f)
+    }
+
+    /// Collect `iter` into a slice in the arena.
+    ///
+    /// Space for the iterator's `size_hint` lower bound is reserved up front.
+    /// If the iterator yields more, the slice grows in place while it is the
+    /// arena's most recent allocation and is copied to a larger region
+    /// otherwise. Unused capacity is given back at the end.
+    ///
+    /// ```
+    /// # use arenars::Arena;
+    /// let arena = Arena::new(256).unwrap();
+    /// let evens = arena.alloc_from_iter((0..20u32).filter(|n| n % 2 == 0)).unwrap();
+    /// assert_eq!(evens.len(), 10);
+    /// assert_eq!(arena.used(), 10 * 4);
+    /// ```
+    ///
+    /// Like [`alloc`](Arena::alloc), the elements are never dropped.
+    pub fn alloc_from_iter<T, I>(&self, iter: I) -> Result<ArenaSlice<'_, T>, ArenaError>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut vec = ArenaVec::new_in(self);
+        vec.extend(iter)?;
+        Ok(ArenaSlice::new(self, vec.into_bump_slice()))
+    }
+
+    /// Low-level allocation based on layout.
+    ///
+    /// Zero-sized layouts never touch the bump pointer: they get a dangling
+    /// pointer aligned to `layout.align()`.
+    fn alloc_layout(&self, layout: Layout) -> Result<NonNull<u8>, ArenaError> {
+        if layout.size() == 0 {
+            return Ok(dangling(layout));
+        }
+
+        match self.bump(layout) {
+            Some(ptr) => Ok(ptr),
+            None => self.alloc_in_next_chunk(layout),
+        }
+    }
+
+    /// Try to carve `layout` out of the current chunk.
+    fn bump(&self, layout: Layout) -> Option<NonNull<u8>> {
+        // Align the actual address, not the offset: the chunk itself is only
+        // guaranteed to be aligned to `config.align`.
+        let base = self.base.get().as_ptr() as usize;
+        let (start, end) = aligned_range(base, self.offset.get(), layout)?;
+
+        if end > self.limit.get() {
+            return None;
+        }
+
+        unsafe {
+            let ptr = self.base.get().as_ptr().add(start);
+            self.offset.set(end);
+            Some(NonNull::new_unchecked(ptr))
+        }
+    }
+
+    /// Slow path: move on to a retained chunk, or grow by a new one.
+    #[cold]
+    fn alloc_in_next_chunk(&self, layout: Layout) -> Result<NonNull<u8>, ArenaError> {
+        if self.config.growth == Growth::Fixed {
+            return self.spill(layout);
+        }
+
+        // Chunks kept by an earlier `reset` are reused before growing.
+        while self.current.get() + 1 < self.chunks.borrow().len() {
+            self.retire_current();
+            if let Some(ptr) = self.bump(layout) {
+                return Ok(ptr);
+            }
+        }
+
+        let mut chunks = self.chunks.borrow_mut();
+        let last = chunks[chunks.len() - 1].size;
+        let next = match self.config.growth {
+            Growth::Fixed => unreachable!(),
+            Growth::Linear => self.config.chunk_size,
+            Growth::Doubling => last.saturating_mul(2).max(self.config.chunk_size),
+        };
+        // A fresh chunk is aligned to `config.align`, so only stricter
+        // alignments need padding.
+        let padding = layout.align().saturating_sub(self.config.align);
+        let required = layout.size()
+            .checked_add(padding)
+            .ok_or(ArenaError::SizeOverflow)?;
+        let size = next.max(required);
+        let capacity = self.capacity.get()
+            .checked_add(size)
+            .ok_or(ArenaError::SizeOverflow)?;
+
+        chunks.push(Chunk::new(size, self.config.align)?);
+        drop(chunks);
+        self.capacity.set(capacity);
+        self.retire_current();
+        self.bump(layout).ok_or_else(|| self.out_of_memory(layout))
+    }
+
+    /// Serve `layout` from the fallback allocator, or fail with
+    /// [`ArenaError::OutOfMemory`] if there is none.
+    #[cold]
+    fn spill(&self, layout: Layout) -> Result<NonNull<u8>, ArenaError> {
+        let Some(fallback) = self.config.fallback else {
+            return Err(self.out_of_memory(layout));
+        };
+
+        let ptr = unsafe { fallback.alloc(layout) };
+        let ptr = NonNull::new(ptr).ok_or(ArenaError::AllocationFailed {
+            size: layout.size(),
+            align: layout.align(),
+        })?;
+        self.spills.borrow_mut().push(Spill { ptr, layout, serial: self.serial.get() });
+
+        let mut stats = self.spill_stats.get();
+        stats.allocations += 1;
+        stats.bytes = stats.bytes.saturating_add(layout.size());
+        stats.live_bytes += layout.size();
+        stats.peak_bytes = stats.peak_bytes.max(stats.live_bytes);
+        self.spill_stats.set(stats);
+        Ok(ptr)
+    }
+
+    /// Free the allocations spilled after checkpoint serial `keep`, newest
+    /// first.
+    fn free_spills(&mut self, keep: usize) {
+        let Some(fallback) = self.config.fallback else {
+            return;
+        };
+
+        let spills = self.spills.get_mut();
+        let kept = spills.partition_point(|spill| spill.serial <= keep);
+        let mut stats = self.spill_stats.get();
+        for spill in spills.drain(kept..).rev() {
+            stats.live_bytes -= spill.layout.size();
+            unsafe { fallback.dealloc(spill.ptr.as_ptr(), spill.layout) };
+        }
+        self.spill_stats.set(stats);
+    }
+
+    /// An [`ArenaError::OutOfMemory`] describing a failed request for `layout`.
+    #[cold]
+    fn out_of_memory(&self, layout: Layout) -> ArenaError {
+        ArenaError::OutOfMemory {
+            size: layout.size(),
+            align: layout.align(),
+            remaining: self.remaining(),
+            capacity: self.capacity(),
+            name: self.config.name,
+        }
+    }
+
+    /// Resize the most recent allocation in place.
+    ///
+    /// Succeeds only if the `old_size` bytes at `ptr` end exactly at the bump
+    /// pointer and `new_size` bytes from `ptr` still fit in the current chunk.
+    /// Shrinking to zero gives the allocation back.
+    pub(crate) fn resize_last(&self, ptr: NonNull<u8>, old_size: usize, new_size: usize) -> bool {
+        let base = self.base.get().as_ptr() as usize;
+        let Some(start) = (ptr.as_ptr() as usize).checked_sub(base) else {
+            return false;
+        };
+        if old_size == 0 || start.checked_add(old_size) != Some(self.offset.get()) {
+            return false;
+        }
+
+        match start.checked_add(new_size) {
+            Some(end) if end <= self.limit.get() => {
+                self.offset.set(end);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Close the current chunk and continue bumping in the next one.
+    fn retire_current(&self) {
+        let next = self.current.get() + 1;
+        let chunks = self.chunks.borrow();
+
+        // The bump pointer never returns to the rest of this chunk, so its
+        // unused tail counts as used.
+        self.retired.set(self.retired.get() + self.limit.get());
+        self.offset.set(0);
+        self.current.set(next);
+        self.base.set(chunks[next].memory);
+        self.limit.set(chunks[next].size);
+    }
+
+    /// Run `f` with a scratch arena whose allocations are all released when
+    /// `f` returns.
+    ///
+    /// The scratch arena starts in the unused tail of the current chunk and
+    /// grows by the same policy as `self`. Because the closure's argument is a
+    /// separate arena, its allocations cannot escape the closure, while the
+    /// return value may still borrow from allocations made before the scope:
+    ///
+    /// ```
+    /// # use arenars::Arena;
+    /// let arena = Arena::new(1024).unwrap();
+    /// let names = arena.alloc_array(3, |i| i as u32 * 10).unwrap();
+    ///
+    /// let largest = arena.scope(|scratch| {
+    ///     let doubled = scratch.alloc_array(names.len(), |i| names[i] * 2).unwrap();
+    ///     let max = doubled.iter().copied().max().unwrap();
+    ///     names.iter().find(|&&n| n * 2 == max).unwrap()
+    /// });
+    ///
+    /// assert_eq!(*largest, 20);
+    /// assert_eq!(arena.used(), 12); // scratch memory was given back
+    /// ```
+    ///
+    /// While the scope is open the tail belongs to the scratch arena, so
+    /// allocating from `self` directly moves on to a new chunk (or fails for
+    /// a [`Growth::Fixed`] arena). In that case the tail is only reclaimed
+    /// by the next [`reset`](Arena::reset).
+    ///
+    /// Allocations the scratch arena spills to the fallback allocator are
+    /// added to this arena's [`spill_stats`](Arena::spill_stats) when `f`
+    /// returns.
+    pub fn scope<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&Arena) -> R,
+    {
+        let index = self.current.get();
+        let start = self.offset.get();
+        let limit = self.limit.get();
+
+        let chunk = Chunk {
+            memory: unsafe { NonNull::new_unchecked(self.base.get().as_ptr().add(start)) },
+            size: limit - start,
+            align: 1,
+            owned: false,
+        };
+        self.offset.set(limit);
+
+        let _restore = ScopeGuard { arena: self, index, start, limit };
+        let scratch = Arena::from_chunk(chunk, self.config.clone());
+        let result = f(&scratch);
+        self.add_scratch_spills(scratch.spill_stats());
+        scratch.check_leaks();
+        result
+    }
+
+    /// Count the spills of a scratch arena from [`scope`](Arena::scope),
+    /// which are all freed when it closes.
+    fn add_scratch_spills(&self, scratch: SpillStats) {
+        let mut stats = self.spill_stats.get();
+        stats.allocations += scratch.allocations;
+        stats.bytes = stats.bytes.saturating_add(scratch.bytes);
+        stats.peak_bytes = stats.peak_bytes.max(stats.live_bytes + scratch.peak_bytes);
+        self.spill_stats.set(stats);
+    }
+
+    /// Record the current bump position so it can be restored with
+    /// [`rewind`](Arena::rewind).
+    pub fn checkpoint(&self) -> Checkpoint {
+        // Everything registered from now on gets a later serial.
+        let serial = self.serial.get();
+        self.serial.set(serial + 1);
+        Checkpoint {
+            arena: self.id,
+            resets: self.resets,
+            serial,
+            index: self.current.get(),
+            offset: self.offset.get(),
+            retired: self.retired.get(),
+        }
+    }
+
+    /// Roll the bump position back to `mark`, releasing every allocation made
+    /// since [`checkpoint`](Arena::checkpoint) returned it.
+    ///
+    /// Like [`reset`](Arena::reset) this takes `&mut self`, so rewinding while
+    /// a reference into the released region is live does not compile:
+    ///
+    /// ```compile_fail
+    /// # use arenars::Arena;
+    /// let mut arena = Arena::new(64).unwrap();
+    /// let mark = arena.checkpoint();
+    /// let r = arena.alloc(1u32).unwrap();
+    /// arena.rewind(mark).unwrap(); // error: `arena` is still borrowed by `r`
+    /// assert_eq!(*r, 1);
+    /// ```
+    ///
+    /// Values registered with [`alloc_with_drop`](Arena::alloc_with_drop)
+    /// after the checkpoint are dropped, and allocations spilled to the
+    /// fallback allocator since then are freed. Chunks allocated after the
+    /// checkpoint are kept for reuse. `mark` itself, and checkpoints taken
+    /// before it, stay valid.
+    ///
+    /// Returns [`ArenaError::InvalidCheckpoint`] if `mark` was taken from
+    /// another arena, before the last `reset`, or lies past the current bump
+    /// position, as a checkpoint taken after one that has since been rewound
+    /// to does until the arena grows past it again. Rewinding to such a mark
+    /// once it is behind the bump position is still safe: every value
+    /// registered since the mark was taken is dropped.
+    pub fn rewind(&mut self, mark: Checkpoint) -> Result<(), ArenaError> {
+        if mark.arena != self.id
+            || mark.resets != self.resets
+            || (mark.index, mark.offset) > (self.current.get(), self.offset.get())
+        {
+            return Err(ArenaError::InvalidCheckpoint);
+        }
+
+        // Destructors may still read spilled memory, so run them first.
+        self.run_drops(mark.serial);
+        self.free_spills(mark.serial);
+
+        let chunk = &self.chunks.get_mut()[mark.index];
+        self.base.set(chunk.memory);
+        self.limit.set(chunk.size);
+        self.current.set(mark.index);
+        self.offset.set(mark.offset);
+        self.retired.set(mark.retired);
+        Ok(())
+    }
+
+    /// Reset the arena (doesn't deallocate, just resets the offset).
+    ///
+    /// Values registered with [`alloc_with_drop`](Arena::alloc_with_drop)
+    /// are dropped in reverse allocation order first, then allocations
+    /// spilled to the fallback allocator are freed.
+    ///
+    /// # Panics
+    /// In debug builds, panics if a type that needs `Drop` was stored with
+    /// [`alloc`](Arena::alloc) or [`alloc_array`](Arena::alloc_array) since
+    /// the last reset, since those values have been leaked. The arena is
+    /// still reset before the panic.
+    ///
+    /// Chunks allocated by growth are kept for reuse unless the arena was
+    /// built with [`ArenaBuilder::retain_chunks`] set to `false`, in which
+    /// case everything but the initial chunk is freed.
+    ///
+    /// Taking `&mut self` means the borrow checker rejects a `reset` while
+    /// any reference handed out by `alloc` or `alloc_array` is still live:
+    ///
+    /// ```compile_fail
+    /// # use arenars::Arena;
+    /// let mut arena = Arena::new(64).unwrap();
+    /// let r = arena.alloc(1u32).unwrap();
+    /// arena.reset(); // error: `arena` is still borrowed by `r`
+    /// assert_eq!(*r, 1);
+    /// ```
+    pub fn reset(&mut self) {
+        self.run_drops(0);
+        self.free_spills(0);
+
+        let chunks = self.chunks.get_mut();
+        if !self.config.retain_chunks {
+            chunks.truncate(1);
+            self.capacity.set(chunks[0].size);
+        }
+        self.base.set(chunks[0].memory);
+        self.limit.set(chunks[0].size);
+        self.current.set(0);
+        self.offset.set(0);
+        self.retired.set(0);
+        self.resets += 1;
+
+        self.check_leaks();
+    }
+
+    /// Remaining space in bytes: the rest of the current chunk plus any
+    /// chunks after it, all of which the bump pointer can still reach.
+    pub fn remaining(&self) -> usize {
+        self.capacity.get() - self.used()
+    }
+
+    /// Used space in bytes, including the unused tails of earlier chunks
+    /// that growth moved past.
+    pub fn used(&self) -> usize {
+        self.retired.get() + self.offset.get()
+    }
+
+    /// Total capacity in bytes.
+    pub fn capacity(&self) -> usize {
+        self.capacity.get()
+    }
+
+    /// Number of chunks currently owned by the arena.
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.borrow().len()
+    }
+
+    /// The name given with [`ArenaBuilder::name`], if any.
+    pub fn name(&self) -> Option<&'static str> {
+        self.config.name
+    }
+
+    /// How much has been served by the fallback allocator configured with
+    /// [`ArenaBuilder::heap_fallback`]. Spilled bytes are not counted by
+    /// [`used`](Arena::used) or [`capacity`](Arena::capacity).
+    pub fn spill_stats(&self) -> SpillStats {
+        self.spill_stats.get()
+    }
+}
+
+impl Drop for Arena {
+    fn drop(&mut self) {
+        // Run registered destructors; spills and chunks are freed afterwards.
+        self.run_drops(0);
+        self.free_spills(0);
+    }
+}
+
+/// Cleans up a partially initialized array if [`Arena::init_array`] stops
+/// early.
+struct InitGuard<'a, T> {
+    arena: &'a Arena,
+    base: *mut T,
+    size: usize, // bytes reserved for the array
+    len: usize,  // elements initialized so far
+}
+
+impl<T> Drop for InitGuard<'_, T> {
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.base, self.len));
+            self.arena.resize_last(NonNull::new_unchecked(self.base as *mut u8), self.size, 0);
+        }
+    }
+}
+
+/// Hands the tail reserved by [`Arena::scope`] back to the parent arena, even
+/// if the closure panics.
+struct ScopeGuard<'a> {
+    arena: &'a Arena,
+    index: usize,
+    start: usize,
+    limit: usize,
+}
+
+impl Drop for ScopeGuard<'_> {
+    fn drop(&mut self) {
+        // If the parent allocated past the reservation in the meantime the
+        // tail stays reserved until the next reset.
+        let arena = self.arena;
+        if arena.current.get() == self.index && arena.offset.get() == self.limit {
+            arena.offset.set(self.start);
+        }
+    }
+}
+
+impl core::fmt::Debug for Arena {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Arena {{ used: {}, capacity: {} }}", self.used(), self.capacity())
+    }
+}
+
+/// Error returned by the arenas in this crate.
+///
+/// Variants carry the numbers needed to diagnose a failure, e.g. how much was
+/// requested and how much was left. Use [`kind`](ArenaError::kind) to match on
+/// the kind of error alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ArenaError {
+    /// A size of zero was passed to a constructor.
+    InvalidSize,
+    /// The requested buffer alignment is not a power of two.
+    InvalidAlignment {
+        align: usize,
+    },
+    /// The global allocator could not provide a buffer.
+    AllocationFailed {
+        size: usize,
+        align: usize,
+    },
+    /// The arena has no room for an allocation of `size` bytes aligned to
+    /// `align`.
+    OutOfMemory {
+        size: usize,
+        align: usize,
+        /// Bytes still free in the arena.
+        remaining: usize,
+        /// Total capacity of the arena in bytes.
+        capacity: usize,
+        /// Name given with [`ArenaBuilder::name`], if any.
+        name: Option<&'static str>,
+    },
+    /// A checkpoint from another arena, or one made stale by a reset.
+    InvalidCheckpoint,
+    /// A size computation overflowed `usize`.
+    SizeOverflow,
+}
+
+/// The kind of an [`ArenaError`], without its data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ArenaErrorKind {
+    InvalidSize,
+    InvalidAlignment,
+    AllocationFailed,
+    OutOfMemory,
+    InvalidCheckpoint,
+    SizeOverflow,
+}
+
+impl ArenaError {
+    /// The kind of this error, for matching without the attached data.
+    ///
+    /// ```
+    /// # use arenars::{Arena, ArenaErrorKind};
+    /// let arena = Arena::new(8).unwrap();
+    /// let err = arena.alloc([0u8; 16]).unwrap_err();
+    /// assert_eq!(err.kind(), ArenaErrorKind::OutOfMemory);
+    /// ```
+    pub fn kind(&self) -> ArenaErrorKind {
+        match self {
+            ArenaError::InvalidSize => ArenaErrorKind::InvalidSize,
+            ArenaError::InvalidAlignment { .. } => ArenaErrorKind::InvalidAlignment,
+            ArenaError::AllocationFailed { .. } => ArenaErrorKind::AllocationFailed,
+            ArenaError::OutOfMemory { .. } => ArenaErrorKind::OutOfMemory,
+            ArenaError::InvalidCheckpoint => ArenaErrorKind::InvalidCheckpoint,
+            ArenaError::SizeOverflow => ArenaErrorKind::SizeOverflow,
+        }
+    }
+}
+
+impl core::fmt::Display for ArenaError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            ArenaError::InvalidSize => write!(f, "Invalid size specified"),
+            ArenaError::InvalidAlignment { align } => {
+                write!(f, "Invalid alignment {}: not a power of two", align)
+            }
+            ArenaError::AllocationFailed { size, align } => {
+                write!(f, "Failed to allocate {} bytes (align {})", size, align)
+            }
+            ArenaError::OutOfMemory { size, align, remaining, capacity, name } => {
+                match name {
+                    Some(name) => write!(f, "Arena '{}' out of memory", name)?,
+                    None => write!(f, "Arena out of memory")?,
+                }
+                write!(
+                    f,
+                    ": requested {} bytes (align {}), {} of {} bytes remaining",
+                    size, align, remaining, capacity
+                )
+            }
+            ArenaError::InvalidCheckpoint => write!(f, "Checkpoint does not belong to this arena"),
+            ArenaError::SizeOverflow => write!(f, "Allocation size overflows usize"),
+        }
+    }
+}
+
+impl core::error::Error for ArenaError {}
+
+/// Error from [`Arena::try_alloc`] and [`TypedArena::try_alloc`]: why the
+/// allocation failed, together with the value that could not be stored.
+pub struct AllocError<T> {
+    /// Why the allocation failed.
+    pub error: ArenaError,
+    /// The value passed to `try_alloc`, handed back to the caller.
+    pub value: T,
+}
+
+impl<T> AllocError<T> {
+    /// Take back the value that could not be allocated.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> From<AllocError<T>> for ArenaError {
+    fn from(error: AllocError<T>) -> Self {
+        error.error
+    }
+}
+
+// Written by hand so that `T` does not need to implement `Debug`.
+impl<T> core::fmt::Debug for AllocError<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("AllocError")
+            .field("error", &self.error)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> core::fmt::Display for AllocError<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.error)
+    }
+}
+
+impl<T> core::error::Error for AllocError<T> {}
+
+/// Error from a fallible initializer such as [`Arena::try_alloc_array`]:
+/// either the arena could not provide the memory, or the initializer itself
+/// failed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AllocOrInitError<E> {
+    /// Reserving memory in the arena failed.
+    Alloc(ArenaError),
+    /// The initializer returned this error.
+    Init(E),
+}
+
+impl<E> From<ArenaError> for AllocOrInitError<E> {
+    fn from(error: ArenaError) -> Self {
+        AllocOrInitError::Alloc(error)
+    }
+}
+
+impl<E: core::fmt::Display> core::fmt::Display for AllocOrInitError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            AllocOrInitError::Alloc(error) => write!(f, "{}", error),
+            AllocOrInitError::Init(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl<E: core::error::Error + 'static> core::error::Error for AllocOrInitError<E> {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        // Display already prints the inner error, so skip to its source.
+        match self {
+            AllocOrInitError::Alloc(error) => error.source(),
+            AllocOrInitError::Init(error) => error.source(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use alloc::string::ToString;
+    use alloc::sync::Arc;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Point {
+        x: f64,
+        y: f64,
+    }
+
+    // Appends its single-digit id to a shared log when dropped, so a log of
+    // 321 means ids 3, 2 and 1 were dropped in that order.
+    struct DropLog {
+        id: usize,
+        log: Arc<AtomicUsize>,
+    }
+
+    impl DropLog {
+        fn new(id: usize, log: &Arc<AtomicUsize>) -> Self {
+            Self { id, log: Arc::clone(log) }
+        }
+    }
+
+    impl Drop for DropLog {
+        fn drop(&mut self) {
+            let id = self.id;
+            self.log
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |v| Some(v * 10 + id))
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_alloc_single() {
+        let arena = Arena::new(1024).unwrap();
+
+        let n = arena.alloc(42u32).unwrap();
+        assert_eq!(*n, 42);
+
+        let p = arena.alloc(Point { x: 1.0, y: 2.0 }).unwrap();
+        assert_eq!(*p, Point { x: 1.0, y: 2.0 });
+    }
+
+    #[test]
+    fn test_try_alloc_returns_value() {
+        let arena = Arena::new(8).unwrap();
+        let first = arena.try_alloc("kept".to_string()).map_err(|e| e.error);
+        assert!(matches!(first, Err(ArenaError::OutOfMemory { .. })));
+
+        let err = arena.try_alloc(alloc::vec![1u8, 2, 3]).unwrap_err();
+        assert_eq!(err.error.kind(), ArenaErrorKind::OutOfMemory);
+        assert!(format!("{:?}", err).starts_with("AllocError { error: OutOfMemory {"));
+        assert!(format!("{:?}", err).ends_with(", .. }"));
+        assert_eq!(err.into_inner(), [1, 2, 3]);
+
+        let n = arena.try_alloc(5u64).unwrap();
+        assert_eq!(*n, 5);
+    }
+
+    #[test]
+    fn test_alloc_with() {
+        let arena = Arena::new(16 * 1024).unwrap();
+        arena.alloc(1u8).unwrap();
+
+        let big = arena.alloc_with(|| [7u64; 1024]).unwrap();
+        assert!(big.iter().all(|&v| v == 7));
+        assert_eq!(addr_of(&*big) % align_of::<u64>(), 0);
+        assert_eq!(arena.used(), 8 + 8 * 1024);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_alloc_with_panic_gives_slot_back() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let arena = Arena::new(64).unwrap();
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            arena.alloc_with(|| -> u64 { panic!("constructor failed") })
+        }));
+        assert!(result.is_err());
+        assert_eq!(arena.used(), 0);
+    }
+
+    #[test]
+    fn test_alloc_array_with_init() {
+        let arena = Arena::new(1024).unwrap();
+
+        // Initialize each element using its index
+        let squares = arena.alloc_array(5, |i| (i * i) as u32).unwrap();
+        assert_eq!(squares, [0, 1, 4, 9, 16]);
+    }
+
+    #[test]
+    fn test_alloc_array_uniform_value() {
+        let arena = Arena::new(1024).unwrap();
+
+        // Uniform init: ignore the index, always return the same value
+        let zeros = arena.alloc_array(4, |_| 0u64).unwrap();
+        assert_eq!(zeros, [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_alloc_str() {
+        let arena = Arena::new(64).unwrap();
+        let a = arena.alloc_str("héllo").unwrap();
+        let b = arena.alloc_str("").unwrap();
+        assert_eq!((&*a, &*b), ("héllo", ""));
+        assert_eq!(arena.used(), "héllo".len());
+
+        assert!(matches!(arena.alloc_str(&"x".repeat(64)), Err(ArenaError::OutOfMemory { .. })));
+    }
+
+    #[test]
+    fn test_alloc_slice_copy_and_clone() {
+        let arena = Arena::new(256).unwrap();
+        let mut copied = arena.alloc_slice_copy(&[1u16, 2, 3]).unwrap();
+        copied[0] = 10;
+        assert_eq!(copied, [10, 2, 3]);
+
+        #[derive(Debug, Clone, PartialEq)]
+        struct Tag(u8); // Clone but not Copy
+
+        let cloned = arena.alloc_slice_clone(&[Tag(1), Tag(2)]).unwrap();
+        assert_eq!(cloned, [Tag(1), Tag(2)]);
+
+        let filled = arena.alloc_slice_fill_with(3, |i| i * 10).unwrap();
+        assert_eq!(filled, [0, 10, 20]);
+    }
+
+    #[test]
+    fn test_alloc_from_iter_exact_size() {
+        let arena = Arena::new(256).unwrap();
+        let squares = arena.alloc_from_iter((0..8u64).map(|i| i * i)).unwrap();
+        assert_eq!(squares[7], 49);
+        assert_eq!(arena.used(), 64);
+    }
+
+    #[test]
+    fn test_alloc_from_iter_unknown_length() {
+        let arena = Arena::builder(64).growth(Growth::Doubling).build().unwrap();
+        arena.alloc(1u8).unwrap();
+
+        // `filter` reports a lower bound of zero, so the slice has to grow.
+        let odd = arena.alloc_from_iter((0..200u32).filter(|n| n % 2 == 1)).unwrap();
+        assert_eq!(odd.len(), 100);
+        assert!(odd.iter().all(|n| n % 2 == 1));
+
+        let empty = arena.alloc_from_iter(core::iter::empty::<u64>()).unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_alloc_from_iter_allocating_iterator() {
+        let arena = Arena::new(1024).unwrap();
+        // Each item also allocates, so the slice cannot grow in place.
+        let refs = arena
+            .alloc_from_iter((0..10u32).filter(|_| true).map(|i| *arena.alloc(i).unwrap()))
+            .unwrap();
+        assert_eq!(refs, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[cfg_attr(miri, ignore)] // leaks on purpose
+    #[should_panic(expected = "leaked values of types that need Drop")]
+    fn test_alloc_from_iter_records_leak() {
+        let mut arena = Arena::new(256).unwrap();
+        arena.alloc_from_iter(["x".to_string()]).unwrap();
+        arena.reset();
+    }
+
+    #[test]
+    fn test_million_objects() {
+        const COUNT: usize = 1_000_000;
+        let arena = Arena::new(COUNT * size_of::<u64>()).unwrap();
+
+        let values = arena.alloc_array(COUNT, |i| i as u64).unwrap();
+
+        for (i, &v) in values.iter().enumerate() {
+            assert_eq!(v, i as u64);
+        }
+
+
+    }
+
+    #[test]
+    fn test_mixed_allocations() {
+        let arena = Arena::new(1024).unwrap();
+
+        let a = arena.alloc(10u64).unwrap();
+        assert_eq!(*a, 10);
+
+        let b = arena.alloc_array(3, |i| i as u32).unwrap();
+        assert_eq!(b, [0, 1, 2]);
+
+        let c = arena.alloc(Point { x: 3.0, y: 4.0 }).unwrap();
+        assert_eq!(c.x, 3.0);
+    }
+
+    #[test]
+    fn test_out_of_memory() {
+        let arena = Arena::new(8).unwrap();
+        arena.alloc(0u64).unwrap();
+        assert!(matches!(arena.alloc(0u64), Err(ArenaError::OutOfMemory { .. })));
+    }
+
+    #[test]
+    fn test_out_of_memory_details() {
+        let arena = Arena::builder(64).name("parser").build().unwrap();
+        arena.alloc(0u8).unwrap();
+
+        let err = arena.alloc([0u32; 32]).unwrap_err();
+        assert_eq!(
+            err,
+            ArenaError::OutOfMemory {
+                size: 128,
+                align: 4,
+                remaining: 63,
+                capacity: 64,
+                name: Some("parser"),
+            }
+        );
+        assert_eq!(err.kind(), ArenaErrorKind::OutOfMemory);
+        assert_eq!(
+            err.to_string(),
+            "Arena 'parser' out of memory: requested 128 bytes (align 4), 63 of 64 bytes remaining"
+        );
+    }
+
+    #[test]
+    fn test_error_is_copy_hash_and_core_error() {
+        use core::hash::{Hash, Hasher};
+
+        struct CountingHasher(usize);
+
+        impl Hasher for CountingHasher {
+            fn finish(&self) -> u64 {
+                self.0 as u64
+            }
+            fn write(&mut self, bytes: &[u8]) {
+                self.0 += bytes.len();
+            }
+        }
+
+        let err = Arena::with_alignment(64, 24).unwrap_err();
+        let copy = err;
+        assert_eq!(err, copy);
+        assert_eq!(err.to_string(), "Invalid alignment 24: not a power of two");
+
+        let mut hasher = CountingHasher(0);
+        err.hash(&mut hasher);
+        assert!(hasher.finish() > 0);
+
+        assert_ne!(err.kind(), ArenaError::SizeOverflow.kind());
+
+        let dyn_err: &dyn core::error::Error = &err;
+        assert!(dyn_err.source().is_none());
+    }
+
+    #[test]
+    fn test_reset_reuses_memory() {
+        let mut arena = Arena::new(64).unwrap();
+
+        {
+            let _x = arena.alloc(1u32).unwrap();
+            assert!(arena.used() > 0);
+        }
+
+        arena.reset();
+        assert_eq!(arena.used(), 0);
+
+        // Can allocate again after reset
+        let y = arena.alloc(99u32).unwrap();
+        assert_eq!(*y, 99);
+    }
+
+    #[test]
+    fn test_heap_fallback_spills_and_frees_on_reset() {
+        let mut arena = Arena::builder(16).heap_fallback(true).build().unwrap();
+        arena.alloc(1u64).unwrap();
+        arena.alloc(2u64).unwrap();
+
+        let big = arena.alloc_array(8, |i| i as u32).unwrap();
+        assert_eq!(big[7], 7);
+        assert_eq!(arena.used(), 16);
+        assert_eq!(
+            arena.spill_stats(),
+            SpillStats { allocations: 1, bytes: 32, live_bytes: 32, peak_bytes: 32 }
+        );
+
+        arena.reset();
+        let stats = arena.spill_stats();
+        assert_eq!((stats.live_bytes, stats.peak_bytes), (0, 32));
+
+        // After the reset the arena has room again, so nothing spills.
+        arena.alloc(3u64).unwrap();
+        assert_eq!(arena.spill_stats().allocations, 1);
+    }
+
+    #[test]
+    fn test_heap_fallback_drops_spilled_values() {
+        let log = Arc::new(AtomicUsize::new(0));
+        let mut arena = Arena::builder(8).heap_fallback(true).build().unwrap();
+
+        arena.alloc_with_drop(DropLog::new(1, &log)).unwrap();
+        arena.alloc_with_drop(DropLog::new(2, &log)).unwrap();
+        assert!(arena.spill_stats().allocations > 0);
+
+        arena.reset();
+        assert_eq!(log.load(Ordering::SeqCst), 21);
+        assert_eq!(arena.spill_stats().live_bytes, 0);
+    }
+
+    #[test]
+    fn test_rewind_frees_later_spills() {
+        let mut arena = Arena::builder(8).heap_fallback(true).build().unwrap();
+        arena.alloc(0u64).unwrap();
+        arena.alloc(1u64).unwrap();
+
+        let mark = arena.checkpoint();
+        arena.alloc(2u64).unwrap();
+        arena.alloc(3u64).unwrap();
+        assert_eq!(arena.spill_stats().live_bytes, 24);
+
+        arena.rewind(mark).unwrap();
+        assert_eq!(arena.spill_stats().live_bytes, 8);
+        assert_eq!(arena.spill_stats().allocations, 3);
+    }
+
+    #[test]
+    fn test_fallback_allocator() {
+        static SPILLED: AtomicUsize = AtomicUsize::new(0);
+
+        struct Counting;
+
+        unsafe impl GlobalAlloc for Counting {
+            unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+                SPILLED.fetch_add(layout.size(), Ordering::SeqCst);
+                unsafe { allocator::alloc(layout) }
+            }
+            unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+                SPILLED.fetch_sub(layout.size(), Ordering::SeqCst);
+                unsafe { allocator::dealloc(ptr, layout) }
+            }
+        }
+
+        static COUNTING: Counting = Counting;
+        let arena = Arena::builder(8).fallback_allocator(&COUNTING).build().unwrap();
+        let values = arena.alloc_array(4, |i| i as u64).unwrap();
+        assert_eq!(values, [0, 1, 2, 3]);
+        assert_eq!(SPILLED.load(Ordering::SeqCst), 32);
+
+        drop(arena);
+        assert_eq!(SPILLED.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_no_fallback_by_default() {
+        let arena = Arena::new(8).unwrap();
+        assert!(arena.alloc(0u128).is_err());
+        assert_eq!(arena.spill_stats(), SpillStats::default());
+    }
+
+    #[test]
+    fn test_debug_format() {
+        let arena = Arena::new(512).unwrap();
+        let s = format!("{:?}", arena);
+        assert_eq!(s, "Arena { used: 0, capacity: 512 }");
+    }
+
+    #[test]
+    fn test_arena_ref_deref() {
+        let arena = Arena::new(1024).unwrap();
+        let mut r = arena.alloc(10u32).unwrap();
+        assert_eq!(*r, 10);
+        *r = 20;
+        assert_eq!(*r, 20);
+    }
+
+    #[test]
+    fn test_arena_ref_debug() {
+        let arena = Arena::new(1024).unwrap();
+        let r = arena.alloc(42u32).unwrap();
+        assert_eq!(format!("{:?}", r), "42");
+    }
+
+    #[test]
+    fn test_arena_ref_leak_and_map() {
+        let arena = Arena::new(1024).unwrap();
+        let r = arena.alloc(Point { x: 1.0, y: 2.0 }).unwrap();
+        let mut y = ArenaRef::map(r, |p| &mut p.y);
+        *y += 1.0;
+        let y: &mut f64 = ArenaRef::leak(y);
+        assert_eq!(*y, 3.0);
+    }
+
+    #[test]
+    fn test_arena_ref_split() {
+        let arena = Arena::new(1024).unwrap();
+        let (mut a, b) = ArenaRef::split(arena.alloc((1u8, 'b')).unwrap());
+        *a += 1;
+        assert_eq!((*a, *b), (2, 'b'));
+
+        let (x, y) = ArenaRef::map_split(arena.alloc(Point { x: 1.0, y: 2.0 }).unwrap(), |p| (&mut p.x, &mut p.y));
+        assert_eq!((*x, *y), (1.0, 2.0));
+
+        let slice: ArenaRef<'_, [u32]> = arena_unsize!(arena.alloc([1u32, 2, 3, 4]).unwrap());
+        let (mut head, tail) = ArenaRef::split_at(slice, 1);
+        head[0] = 10;
+        assert_eq!((&*head, &*tail), (&[10][..], &[2, 3, 4][..]));
+    }
+
+    #[test]
+    fn test_arena_ref_forwarding_traits() {
+        use alloc::collections::BTreeMap;
+        use core::borrow::Borrow;
+
+        let arena = Arena::new(1024).unwrap();
+        let mut names = BTreeMap::new();
+        names.insert(arena.alloc(2u32).unwrap(), "two");
+        names.insert(arena.alloc(1u32).unwrap(), "one");
+        assert_eq!(names.get(&2), Some(&"two"));
+        assert_eq!(names.keys().map(|k| **k).collect::<Vec<_>>(), [1, 2]);
+
+        let a = arena.alloc(5i64).unwrap();
+        let b = arena.alloc(5i64).unwrap();
+        assert_eq!(a, b);
+        assert!(a <= b);
+        assert_eq!(format!("{a}"), "5");
+        let borrowed: &i64 = a.borrow();
+        assert_eq!(a.as_ref(), borrowed);
+    }
+
+    #[test]
+    fn test_arena_ref_pin_projection() {
+        let arena = Arena::new(1024).unwrap();
+        let pinned = Pin::new(arena.alloc((7u8, Point { x: 1.0, y: 2.0 })).unwrap());
+        let point = unsafe { ArenaRef::map_pinned(pinned, |(_, p)| p) };
+        let point: Pin<&mut Point> = ArenaRef::leak_pinned(point);
+        assert_eq!(point.x, 1.0);
+    }
+
+    #[test]
+    fn test_reset_allowed_after_ref_dropped() {
+        let mut arena = Arena::new(1024).unwrap();
+        {
+            let _r = arena.alloc(1u32).unwrap();
+            // cannot call arena.reset() here — borrow checker prevents it
+        } // _r dropped here, borrow released
+        arena.reset(); // fine now
+        assert_eq!(arena.used(), 0);
+    }
+
+    #[test]
+    fn test_alloc_uninit_single() {
+        let arena = Arena::new(1024).unwrap();
+
+        let mut slot = arena.alloc_uninit::<u64>().unwrap();
+        slot.write(77);
+        let val = unsafe { slot.assume_init_mut() };
+        assert_eq!(*val, 77);
+    }
+
+    #[test]
+    fn test_alloc_array_uninit() {
+        let arena = Arena::new(1024).unwrap();
+
+        let mut slots = arena.alloc_array_uninit::<u32>(4).unwrap();
+        for (i, slot) in slots.iter_mut().enumerate() {
+            slot.write(i as u32 * 10);
+        }
+        let vals: &[u32] = unsafe { core::mem::transmute(&*slots) };
+        assert_eq!(vals, [0, 10, 20, 30]);
+    }
+
+    #[test]
+    fn test_zero_size_arena_fails() {
+        assert!(matches!(Arena::new(0), Err(ArenaError::InvalidSize)));
+    }
+
+    #[test]
+    fn test_empty_array_alloc() {
+        let arena = Arena::new(64).unwrap();
+        let empty = arena.alloc_array::<u32, _>(0, |_| 0).unwrap();
+        assert!(empty.is_empty());
+        assert_eq!(arena.used(), 0); // nothing consumed
+    }
+
+    #[test]
+    fn test_many_live_refs() {
+        let arena = Arena::new(1024).unwrap();
+
+        let a = arena.alloc(1u32).unwrap();
+        let mut b = arena.alloc(2u32).unwrap();
+        let mut c = arena.alloc_array(3, |i| i as u32).unwrap();
+
+        *b += *a;
+        c[0] = *b;
+        assert_eq!(*a, 1);
+        assert_eq!(*b, 3);
+        assert_eq!(c, [3, 1, 2]);
+    }
+
+    #[test]
+    fn test_live_refs_survive_growth() {
+        let arena = Arena::builder(16).growth(Growth::Doubling).build().unwrap();
+
+        let refs = arena.alloc_array(8, |i| arena.alloc(i as u64).unwrap()).unwrap();
+        assert!(arena.chunk_count() > 1);
+        for (i, r) in refs.iter().enumerate() {
+            assert_eq!(**r, i as u64);
+        }
+    }
+
+    #[test]
+    fn test_fixed_arena_does_not_grow() {
+        let arena = Arena::new(16).unwrap();
+        arena.alloc_array(2, |_| 0u64).unwrap();
+        assert!(matches!(arena.alloc(0u64), Err(ArenaError::OutOfMemory { .. })));
+        assert_eq!(arena.chunk_count(), 1);
+    }
+
+    #[test]
+    fn test_growable_allocates_new_chunk() {
+        let arena = Arena::builder(16).growth(Growth::Linear).build().unwrap();
+
+        let first: *const u64 = &*arena.alloc(7u64).unwrap();
+        for i in 0..10u64 {
+            arena.alloc(i).unwrap();
+        }
+
+        assert!(arena.chunk_count() > 1);
+        assert_eq!(arena.used(), 11 * size_of::<u64>());
+        assert_eq!(arena.remaining(), 8);
+        // Growth never moves earlier allocations.
+        assert_eq!(unsafe { *first }, 7);
+    }
+
+    #[test]
+    fn test_growth_counts_skipped_tail_as_used() {
+        let arena = Arena::builder(16).growth(Growth::Linear).build().unwrap();
+        arena.alloc(1u64).unwrap();
+        arena.alloc([0u8; 12]).unwrap(); // does not fit the 8 bytes left
+
+        assert_eq!(arena.capacity(), 32);
+        assert_eq!(arena.used(), 16 + 12);
+        assert_eq!(arena.remaining(), 4);
+    }
+
+    #[test]
+    fn test_doubling_growth_fits_large_request() {
+        let arena = Arena::builder(16).growth(Growth::Doubling).build().unwrap();
+
+        arena.alloc(1u64).unwrap();
+        let big = arena.alloc_array(100, |i| i as u64).unwrap();
+        assert_eq!(big[99], 99);
+        assert!(arena.capacity() >= 16 + 100 * size_of::<u64>());
+    }
+
+    #[test]
+    fn test_reset_retains_chunks() {
+        let mut arena = Arena::builder(16).growth(Growth::Linear).build().unwrap();
+        arena.alloc_array(6, |_| 0u64).unwrap();
+        let chunks = arena.chunk_count();
+        let capacity = arena.capacity();
+
+        arena.reset();
+        assert_eq!(arena.used(), 0);
+        assert_eq!(arena.chunk_count(), chunks);
+        assert_eq!(arena.capacity(), capacity);
+
+        // Retained chunks are reused instead of allocating new ones.
+        for i in 0..4u64 {
+            arena.alloc(i).unwrap();
+        }
+        assert_eq!(arena.chunk_count(), chunks);
+    }
+
+    #[test]
+    fn test_reset_releases_chunks() {
+        let mut arena = Arena::builder(16)
+            .growth(Growth::Linear)
+            .retain_chunks(false)
+            .build()
+            .unwrap();
+        arena.alloc_array(6, |_| 0u64).unwrap();
+        assert!(arena.chunk_count() > 1);
+
+        arena.reset();
+        assert_eq!(arena.chunk_count(), 1);
+        assert_eq!(arena.capacity(), 16);
+    }
+
+    #[test]
+    fn test_rewind_releases_later_allocations() {
+        let mut arena = Arena::new(64).unwrap();
+        arena.alloc(1u64).unwrap();
+
+        let mark = arena.checkpoint();
+        arena.alloc_array(4, |i| i as u64).unwrap();
+        assert_eq!(arena.used(), 40);
+
+        arena.rewind(mark).unwrap();
+        assert_eq!(arena.used(), 8);
+        let r = arena.alloc(2u64).unwrap();
+        assert_eq!(*r, 2);
+        assert_eq!(arena.used(), 16);
+    }
 
-        unsafe {
-            let typed_ptr = ptr.as_ptr() as *mut core::mem::MaybeUninit<T>;
-            Ok(ArenaRef { inner: &mut *typed_ptr })
+    #[test]
+    fn test_rewind_across_chunks() {
+        let mut arena = Arena::builder(16).growth(Growth::Linear).build().unwrap();
+        arena.alloc(1u64).unwrap();
+
+        let mark = arena.checkpoint();
+        for i in 0..6u64 {
+            arena.alloc(i).unwrap();
         }
+        let chunks = arena.chunk_count();
+        assert!(chunks > 1);
+
+        arena.rewind(mark).unwrap();
+        assert_eq!(arena.used(), 8);
+        assert_eq!(arena.chunk_count(), chunks); // kept for reuse
     }
 
-    /// Allocate space for an array of `count` elements without initializing them.
-    ///
-    /// Returns `&mut [MaybeUninit<T>]`. The caller **must** initialize every
-    /// element before reading from the slice.
-    ///
-    /// Prefer [`alloc_array`] unless you have a specific performance reason to
-    /// skip initialization.
-    pub fn alloc_array_uninit<T>(
-        &mut self,
-        count: usize,
-    ) -> Result<&mut [core::mem::MaybeUninit<T>], ArenaError> {
-        if count == 0 {
-            return Ok(&mut []);
-        }
+    #[test]
+    fn test_rewind_rejects_foreign_checkpoint() {
+        let other = Arena::new(64).unwrap();
+        let mut arena = Arena::new(64).unwrap();
 
-        let layout = Layout::array::<T>(count)
-            .map_err(|_| ArenaError::InvalidSize)?;
-        let ptr = self.alloc_layout(layout)?;
+        let mark = other.checkpoint();
+        assert_eq!(arena.rewind(mark), Err(ArenaError::InvalidCheckpoint));
 
-        unsafe {
-            let base = ptr.as_ptr() as *mut core::mem::MaybeUninit<T>;
-            Ok(core::slice::from_raw_parts_mut(base, count))
-        }
+        // A new arena may get the freed buffer of a dropped one.
+        let mark = other.checkpoint();
+        drop(other);
+        let mut reused = Arena::new(64).unwrap();
+        assert_eq!(reused.rewind(mark), Err(ArenaError::InvalidCheckpoint));
     }
 
-    /// Low-level allocation based on layout.
-    fn alloc_layout(&mut self, layout: Layout) -> Result<NonNull<u8>, ArenaError> {
-        let size = layout.size();
-        let align = layout.align();
+    #[test]
+    fn test_rewind_rejects_stale_checkpoint() {
+        let mut arena = Arena::new(64).unwrap();
+        arena.alloc(1u64).unwrap();
+        let mark = arena.checkpoint();
 
-        let aligned_offset = (self.offset + align - 1) & !(align - 1);
+        arena.reset();
+        assert_eq!(arena.rewind(mark), Err(ArenaError::InvalidCheckpoint));
+    }
 
-        if aligned_offset + size > self.size {
-            return Err(ArenaError::OutOfMemory);
-        }
+    #[test]
+    fn test_rewind_rejects_checkpoint_from_before_reset_after_regrowing() {
+        let mut arena = Arena::new(64).unwrap();
+        arena.alloc(1u64).unwrap();
+        let mark = arena.checkpoint();
 
-        unsafe {
-            let ptr = self.memory.as_ptr().add(aligned_offset);
-            self.offset = aligned_offset + size;
-            Ok(NonNull::new_unchecked(ptr))
-        }
+        arena.reset();
+        arena.alloc_array(4, |i| i as u64).unwrap();
+        assert_eq!(arena.rewind(mark), Err(ArenaError::InvalidCheckpoint));
+        assert_eq!(arena.used(), 32);
     }
 
-    /// Reset the arena (doesn't deallocate, just resets the offset).
-    ///
-    /// # Safety note
-    /// All references handed out by `alloc` and `alloc_array` are invalidated
-    /// after this call. Rust's borrow checker cannot enforce this because the
-    /// arena owns the backing memory independently of the returned references.
-    /// Callers must ensure no live references remain before calling `reset`.
-    pub fn reset(&mut self) {
-        self.offset = 0;
+    #[test]
+    fn test_rewind_nested_checkpoints() {
+        let mut arena = Arena::new(64).unwrap();
+        let outer = arena.checkpoint();
+        arena.alloc(1u64).unwrap();
+        let inner = arena.checkpoint();
+        arena.alloc(2u64).unwrap();
+
+        arena.rewind(inner).unwrap();
+        arena.alloc(3u64).unwrap();
+        arena.rewind(inner).unwrap(); // the same mark can be reused
+        assert_eq!(arena.used(), 8);
+
+        arena.rewind(outer).unwrap();
+        assert_eq!(arena.used(), 0);
+        // `inner` was taken after `outer`, so it now lies past the arena's end.
+        assert_eq!(arena.rewind(inner), Err(ArenaError::InvalidCheckpoint));
     }
 
-    /// Remaining space in bytes.
-    pub fn remaining(&self) -> usize {
-        self.size - self.offset
+    #[test]
+    fn test_rewind_to_stale_checkpoint_drops_later_values() {
+        let log = Arc::new(AtomicUsize::new(0));
+        let mut arena = Arena::new(1024).unwrap();
+        let outer = arena.checkpoint();
+        arena.alloc_with_drop(DropLog::new(1, &log)).unwrap();
+        let inner = arena.checkpoint();
+
+        arena.rewind(outer).unwrap();
+        assert_eq!(log.load(Ordering::SeqCst), 1);
+
+        // Grown past `inner` again: the values registered since `inner` was
+        // taken sit below it, but are dropped all the same.
+        arena.alloc_with_drop(DropLog::new(2, &log)).unwrap();
+        arena.alloc_with_drop(DropLog::new(3, &log)).unwrap();
+        arena.rewind(inner).unwrap();
+        assert_eq!(log.load(Ordering::SeqCst), 132);
+
+        arena.reset(); // nothing is dropped twice
+        assert_eq!(log.load(Ordering::SeqCst), 132);
     }
 
-    /// Used space in bytes.
-    pub fn used(&self) -> usize {
-        self.offset
+    #[test]
+    fn test_scope_releases_scratch() {
+        let arena = Arena::new(256).unwrap();
+        arena.alloc(1u64).unwrap();
+
+        let sum = arena.scope(|scratch| {
+            let values = scratch.alloc_array(8, |i| i as u64).unwrap();
+            values.iter().sum::<u64>()
+        });
+
+        assert_eq!(sum, 28);
+        assert_eq!(arena.used(), 8);
     }
 
-    /// Total capacity in bytes.
-    pub fn capacity(&self) -> usize {
-        self.size
+    #[test]
+    fn test_scope_result_borrows_parent() {
+        let arena = Arena::new(256).unwrap();
+        let before = arena.alloc(Point { x: 1.0, y: 2.0 }).unwrap();
+
+        let x = arena.scope(|scratch| {
+            let tmp = scratch.alloc(3.0f64).unwrap();
+            assert_eq!(*tmp, 3.0);
+            &before.x
+        });
+        assert_eq!(*x, 1.0);
     }
-}
 
-impl core::fmt::Debug for Arena {
-    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "Arena {{ used: {}, capacity: {} }}", self.used(), self.capacity())
+    #[test]
+    fn test_nested_scopes() {
+        let arena = Arena::new(256).unwrap();
+
+        arena.scope(|outer| {
+            let a = outer.alloc(1u32).unwrap();
+            outer.scope(|inner| {
+                inner.alloc_array(4, |_| 0u64).unwrap();
+            });
+            assert_eq!(outer.used(), 4);
+            assert_eq!(*a, 1);
+        });
+        assert_eq!(arena.used(), 0);
     }
-}
 
-impl Drop for Arena {
-    fn drop(&mut self) {
-        unsafe {
-            let layout = Layout::from_size_align_unchecked(self.size, 8);
-            allocator::dealloc(self.memory.as_ptr(), layout);
-        }
+    #[test]
+    fn test_scope_grows_with_parent_policy() {
+        let arena = Arena::builder(32).growth(Growth::Linear).build().unwrap();
+        arena.alloc(1u64).unwrap();
+
+        arena.scope(|scratch| {
+            let big = scratch.alloc_array(16, |i| i as u64).unwrap();
+            assert_eq!(big[15], 15);
+            assert!(scratch.chunk_count() > 1);
+        });
+        assert_eq!(arena.used(), 8);
+        assert_eq!(arena.chunk_count(), 1); // scratch chunks were freed
     }
-}
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum ArenaError {
-    InvalidSize,
-    InvalidAlignment,
-    AllocationFailed,
-    OutOfMemory,
-}
+    #[test]
+    fn test_parent_alloc_during_scope_does_not_overlap() {
+        let arena = Arena::new(64).unwrap();
+
+        arena.scope(|scratch| {
+            scratch.alloc(1u64).unwrap();
+            // The tail belongs to the scratch arena and the parent cannot grow.
+            assert!(matches!(arena.alloc(2u64), Err(ArenaError::OutOfMemory { .. })));
+        });
+        assert_eq!(arena.used(), 0);
+    }
 
-impl core::fmt::Display for ArenaError {
-    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        match self {
-            ArenaError::InvalidSize => write!(f, "Invalid size specified"),
-            ArenaError::InvalidAlignment => write!(f, "Invalid alignment"),
-            ArenaError::AllocationFailed => write!(f, "Failed to allocate memory"),
-            ArenaError::OutOfMemory => write!(f, "Arena out of memory"),
-        }
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_arena_is_send() {
+        let arena = Arena::builder(64).growth(Growth::Linear).build().unwrap();
+        arena.alloc_array(16, |i| i as u64).unwrap();
+
+        let mut arena = std::thread::spawn(move || {
+            let r = arena.alloc(42u64).unwrap();
+            assert_eq!(*r, 42);
+            arena
+        })
+        .join()
+        .unwrap();
+
+        arena.reset();
+        assert_eq!(arena.used(), 0);
     }
-}
 
-#[cfg(feature = "std")]
-impl std::error::Error for ArenaError {}
+    #[test]
+    fn test_alloc_with_drop_runs_on_reset() {
+        let log = Arc::new(AtomicUsize::new(0));
+        let mut arena = Arena::new(1024).unwrap();
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use alloc::format;
+        arena.alloc_with_drop(DropLog::new(1, &log)).unwrap();
+        arena.alloc(5u32).unwrap();
+        arena.alloc_with_drop(DropLog::new(2, &log)).unwrap();
+        assert_eq!(log.load(Ordering::SeqCst), 0);
 
-    #[derive(Debug, Clone, Copy, PartialEq)]
-    struct Point {
-        x: f64,
-        y: f64,
+        arena.reset();
+        assert_eq!(log.load(Ordering::SeqCst), 21); // reverse allocation order
+
+        arena.reset();
+        assert_eq!(log.load(Ordering::SeqCst), 21); // never dropped twice
     }
 
     #[test]
-    fn test_alloc_single() {
+    fn test_alloc_with_drop_runs_on_arena_drop() {
+        let log = Arc::new(AtomicUsize::new(0));
+        {
+            let arena = Arena::builder(64).growth(Growth::Linear).build().unwrap();
+            for id in 1..=5 {
+                arena.alloc_with_drop(DropLog::new(id, &log)).unwrap();
+            }
+            assert!(arena.chunk_count() > 1);
+        }
+        assert_eq!(log.load(Ordering::SeqCst), 54321);
+    }
+
+    #[test]
+    fn test_alloc_array_with_drop() {
         let mut arena = Arena::new(1024).unwrap();
+        let names = arena.alloc_array_with_drop(3, |i| i.to_string()).unwrap();
+        assert_eq!(names, ["0", "1", "2"]);
+        arena.reset();
+        assert_eq!(arena.used(), 0);
+    }
 
-        let n = arena.alloc(42u32).unwrap();
-        assert_eq!(*n, 42);
+    #[test]
+    fn test_alloc_with_drop_out_of_space_wastes_nothing() {
+        let needed = size_of::<Vec<u8>>() + size_of::<DropEntry>();
+        let arena = Arena::new(needed - 1).unwrap();
+        assert!(matches!(arena.alloc_with_drop(Vec::<u8>::new()), Err(ArenaError::OutOfMemory { .. })));
+        assert!(matches!(arena.alloc_pinned(Vec::<u8>::new()), Err(ArenaError::OutOfMemory { .. })));
+        assert_eq!(arena.used(), 0);
 
-        let p = arena.alloc(Point { x: 1.0, y: 2.0 }).unwrap();
-        assert_eq!(*p, Point { x: 1.0, y: 2.0 });
+        let arena = Arena::new(needed).unwrap();
+        arena.alloc_with_drop(Vec::<u8>::new()).unwrap();
+        assert_eq!(arena.used(), needed);
     }
 
     #[test]
-    fn test_alloc_array_with_init() {
+    fn test_alloc_with_drop_plain_data_has_no_entry() {
+        let arena = Arena::new(64).unwrap();
+        arena.alloc_with_drop(1u64).unwrap();
+        assert_eq!(arena.used(), 8);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_alloc_array_panic_rolls_back() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let log = Arc::new(AtomicUsize::new(0));
         let mut arena = Arena::new(1024).unwrap();
+        arena.alloc(0u64).unwrap();
 
-        // Initialize each element using its index
-        let squares = arena.alloc_array(5, |i| (i * i) as u32).unwrap();
-        assert_eq!(squares, [0, 1, 4, 9, 16]);
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            arena.alloc_array(4, |i| {
+                assert!(i < 3, "init failed");
+                DropLog::new(i + 1, &log)
+            })
+        }));
+        assert!(result.is_err());
+        assert_eq!(log.load(Ordering::SeqCst), 123);
+        assert_eq!(arena.used(), 8);
+
+        // Nothing was leaked, so reset must not report the panicked type.
+        arena.reset();
     }
 
+    #[cfg(feature = "std")]
     #[test]
-    fn test_alloc_array_uniform_value() {
-        let mut arena = Arena::new(1024).unwrap();
+    fn test_alloc_array_with_drop_panic_rolls_back() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let log = Arc::new(AtomicUsize::new(0));
+        let arena = Arena::new(1024).unwrap();
+
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            arena.alloc_array_with_drop(3, |i| {
+                assert!(i < 2, "init failed");
+                DropLog::new(i + 1, &log)
+            })
+        }));
+        assert!(result.is_err());
+        assert_eq!(log.load(Ordering::SeqCst), 12);
+        assert_eq!(arena.used(), 0);
 
-        // Uniform init: ignore the index, always return the same value
-        let zeros = arena.alloc_array(4, |_| 0u64).unwrap();
-        assert_eq!(zeros, [0, 0, 0, 0]);
+        drop(arena); // no entry was registered, so nothing is dropped twice
+        assert_eq!(log.load(Ordering::SeqCst), 12);
     }
 
     #[test]
-    fn test_million_objects() {
-        const COUNT: usize = 1_000_000;
-        let mut arena = Arena::new(COUNT * size_of::<u64>()).unwrap();
+    fn test_try_alloc_array() {
+        let log = Arc::new(AtomicUsize::new(0));
+        let arena = Arena::new(1024).unwrap();
+
+        let result = arena.try_alloc_array(3, |i| match i {
+            2 => Err("bad element"),
+            _ => Ok(DropLog::new(i + 1, &log)),
+        });
+        assert!(matches!(result, Err(AllocOrInitError::Init("bad element"))));
+        assert_eq!(log.load(Ordering::SeqCst), 12);
+        assert_eq!(arena.used(), 0);
 
-        let values = arena.alloc_array(COUNT, |i| i as u64).unwrap();
+        let ok = arena.try_alloc_array(3, |i| Ok::<_, ()>(i as u32)).unwrap();
+        assert_eq!(ok, [0, 1, 2]);
 
-        for (i, &v) in values.iter().enumerate() {
-            assert_eq!(v, i as u64);
-        }
+        let oom = arena.try_alloc_array(1024, |_| Ok::<_, ()>(0u64));
+        assert!(matches!(oom, Err(AllocOrInitError::Alloc(ArenaError::OutOfMemory { .. }))));
+    }
+
+    #[test]
+    fn test_try_alloc_with() {
+        let arena = Arena::new(64).unwrap();
+
+        let failed = arena.try_alloc_with(|| "x".parse::<u64>());
+        assert!(matches!(failed, Err(AllocOrInitError::Init(_))));
+        assert_eq!(arena.used(), 0);
 
+        let parsed = arena.try_alloc_with(|| "42".parse::<u64>()).unwrap();
+        assert_eq!(*parsed, 42);
+        assert_eq!(arena.used(), 8);
+    }
 
+    #[test]
+    fn test_try_alloc_with_keeps_later_allocations() {
+        let arena = Arena::new(64).unwrap();
+
+        // The closure allocates after the slot, so the slot cannot be given
+        // back, but the inner allocation must stay intact.
+        let mut inner = None;
+        let result = arena.try_alloc_with(|| {
+            inner = Some(arena.alloc(7u32).unwrap());
+            Err::<u64, _>(())
+        });
+        assert!(result.is_err());
+        assert_eq!(*inner.unwrap(), 7);
+        assert_eq!(arena.used(), 12);
     }
 
     #[test]
-    fn test_mixed_allocations() {
+    fn test_rewind_drops_later_values() {
+        let log = Arc::new(AtomicUsize::new(0));
         let mut arena = Arena::new(1024).unwrap();
 
-        let a = arena.alloc(10u64).unwrap();
-        assert_eq!(*a, 10);
+        arena.alloc_with_drop(DropLog::new(1, &log)).unwrap();
+        let mark = arena.checkpoint();
+        arena.alloc_with_drop(DropLog::new(2, &log)).unwrap();
+        arena.alloc_with_drop(DropLog::new(3, &log)).unwrap();
 
-        let b = arena.alloc_array(3, |i| i as u32).unwrap();
-        assert_eq!(b, [0, 1, 2]);
+        arena.rewind(mark).unwrap();
+        assert_eq!(log.load(Ordering::SeqCst), 32);
 
-        let c = arena.alloc(Point { x: 3.0, y: 4.0 }).unwrap();
-        assert_eq!(c.x, 3.0);
+        drop(arena);
+        assert_eq!(log.load(Ordering::SeqCst), 321);
     }
 
     #[test]
-    fn test_out_of_memory() {
-        let mut arena = Arena::new(8).unwrap();
-        arena.alloc(0u64).unwrap();
-        assert!(matches!(arena.alloc(0u64), Err(ArenaError::OutOfMemory)));
+    fn test_scope_drops_scratch_values() {
+        let log = Arc::new(AtomicUsize::new(0));
+        let arena = Arena::new(1024).unwrap();
+
+        arena.scope(|scratch| {
+            scratch.alloc_with_drop(DropLog::new(1, &log)).unwrap();
+        });
+        assert_eq!(log.load(Ordering::SeqCst), 1);
     }
 
+    #[cfg(debug_assertions)]
     #[test]
-    fn test_reset_reuses_memory() {
-        let mut arena = Arena::new(64).unwrap();
-
-        {
-            let _x = arena.alloc(1u32).unwrap();
-            assert!(arena.used() > 0);
-        }
-
+    #[cfg_attr(miri, ignore)] // leaks on purpose
+    #[should_panic(expected = "alloc::string::String")]
+    fn test_reset_reports_leaked_drop_type() {
+        let mut arena = Arena::new(1024).unwrap();
+        arena.alloc("leaked".to_string()).unwrap();
         arena.reset();
-        assert_eq!(arena.used(), 0);
+    }
 
-        // Can allocate again after reset
-        let y = arena.alloc(99u32).unwrap();
-        assert_eq!(*y, 99);
+    #[test]
+    fn test_scope_spills_count_towards_parent() {
+        let arena = Arena::builder(64).heap_fallback(true).build().unwrap();
+        arena.alloc_array(64, |i| i as u8).unwrap();
+        arena.alloc([0u8; 16]).unwrap(); // spills 16 bytes
+
+        arena.scope(|scratch| {
+            scratch.alloc([0u8; 32]).unwrap();
+            scratch.alloc([0u8; 8]).unwrap();
+        });
+
+        let stats = arena.spill_stats();
+        assert_eq!(stats.allocations, 3);
+        assert_eq!(stats.bytes, 56);
+        assert_eq!(stats.live_bytes, 16);
+        assert_eq!(stats.peak_bytes, 56);
     }
 
+    #[cfg(debug_assertions)]
     #[test]
-    fn test_debug_format() {
-        let arena = Arena::new(512).unwrap();
-        let s = format!("{:?}", arena);
-        assert_eq!(s, "Arena { used: 0, capacity: 512 }");
+    #[cfg_attr(miri, ignore)] // leaks on purpose
+    #[should_panic(expected = "Arena leaked values of types that need Drop")]
+    fn test_scope_reports_leaked_drop_type() {
+        let arena = Arena::new(1024).unwrap();
+        arena.scope(|scratch| {
+            scratch.alloc_array(2, |i| alloc::vec![i]).unwrap();
+        });
     }
 
     #[test]
-    fn test_arena_ref_deref() {
+    fn test_reset_accepts_plain_and_registered_values() {
         let mut arena = Arena::new(1024).unwrap();
-        let mut r = arena.alloc(10u32).unwrap();
-        assert_eq!(*r, 10);
-        *r = 20;
-        assert_eq!(*r, 20);
+        arena.alloc(1u64).unwrap();
+        arena.alloc_array(2, |i| i as u8).unwrap();
+        arena.alloc_with_drop("owned".to_string()).unwrap();
+        arena.reset(); // nothing leaked, no panic
+    }
+
+    #[repr(align(16))]
+    struct Align16(u8);
+    #[repr(align(32))]
+    struct Align32(u8);
+    #[repr(align(64))]
+    struct Align64(u8);
+    #[repr(align(4096))]
+    struct Align4096(u8);
+
+    fn addr_of<T>(r: &T) -> usize {
+        r as *const T as usize
     }
 
     #[test]
-    fn test_arena_ref_debug() {
-        let mut arena = Arena::new(1024).unwrap();
-        let r = arena.alloc(42u32).unwrap();
-        assert_eq!(format!("{:?}", r), "42");
+    fn test_over_aligned_types() {
+        let arena = Arena::new(16 * 1024).unwrap();
+
+        // A 1-byte allocation first pushes every later one off alignment.
+        arena.alloc(1u8).unwrap();
+        let a = arena.alloc(Align16(1)).unwrap();
+        assert_eq!((addr_of(&*a) % 16, a.0), (0, 1));
+        arena.alloc(1u8).unwrap();
+        let b = arena.alloc(Align32(2)).unwrap();
+        assert_eq!((addr_of(&*b) % 32, b.0), (0, 2));
+        arena.alloc(1u8).unwrap();
+        assert_eq!(addr_of(&*arena.alloc(Align64(3)).unwrap()) % 64, 0);
+        arena.alloc(1u8).unwrap();
+        let page = arena.alloc(Align4096(4)).unwrap();
+        assert_eq!(addr_of(&*page) % 4096, 0);
+        assert_eq!(page.0, 4);
     }
 
     #[test]
-    fn test_reset_allowed_after_ref_dropped() {
-        let mut arena = Arena::new(1024).unwrap();
-        {
-            let _r = arena.alloc(1u32).unwrap();
-            // cannot call arena.reset() here — borrow checker prevents it
-        } // _r dropped here, borrow released
-        arena.reset(); // fine now
-        assert_eq!(arena.used(), 0);
+    fn test_over_aligned_array() {
+        let arena = Arena::new(1024).unwrap();
+        arena.alloc(1u8).unwrap();
+        let items = arena.alloc_array(4, |i| Align64(i as u8)).unwrap();
+        for (i, item) in items.iter().enumerate() {
+            assert_eq!(addr_of(item) % 64, 0);
+            assert_eq!(item.0, i as u8);
+        }
     }
 
     #[test]
-    fn test_alloc_uninit_single() {
-        let mut arena = Arena::new(1024).unwrap();
+    fn test_over_aligned_growth() {
+        let arena = Arena::builder(64).growth(Growth::Linear).build().unwrap();
+        arena.alloc(1u8).unwrap();
+        let page = arena.alloc(Align4096(9)).unwrap();
+        assert_eq!(addr_of(&*page) % 4096, 0);
+        assert_eq!(arena.chunk_count(), 2);
+    }
 
-        let mut slot = arena.alloc_uninit::<u64>().unwrap();
-        slot.write(77);
-        let val = unsafe { slot.assume_init_mut() };
-        assert_eq!(*val, 77);
+    #[test]
+    fn test_with_alignment_base() {
+        for align in [16, 32, 64, 4096] {
+            let arena = Arena::with_alignment(8192, align).unwrap();
+            assert_eq!(addr_of(&*arena.alloc(0u8).unwrap()) % align, 0);
+        }
     }
 
     #[test]
-    fn test_alloc_array_uninit() {
-        let mut arena = Arena::new(1024).unwrap();
+    fn test_with_alignment_rejects_non_power_of_two() {
+        assert!(matches!(Arena::with_alignment(64, 24), Err(ArenaError::InvalidAlignment { .. })));
+    }
 
-        let slots = arena.alloc_array_uninit::<u32>(4).unwrap();
-        for (i, slot) in slots.iter_mut().enumerate() {
-            slot.write(i as u32 * 10);
+    #[test]
+    fn test_array_size_overflow() {
+        let arena = Arena::new(64).unwrap();
+        assert!(matches!(
+            arena.alloc_array_uninit::<u64>(usize::MAX / 4),
+            Err(ArenaError::SizeOverflow)
+        ));
+        assert!(matches!(
+            arena.alloc_array(usize::MAX, |_| 0u16),
+            Err(ArenaError::SizeOverflow)
+        ));
+        assert_eq!(arena.used(), 0);
+    }
+
+    #[test]
+    fn test_aligned_range_overflow() {
+        let layout = Layout::from_size_align(16, 8).unwrap();
+        assert_eq!(aligned_range(usize::MAX - 4, 0, layout), None);
+        assert_eq!(aligned_range(usize::MAX - 8, 8, layout), None);
+        assert_eq!(aligned_range(4, 0, layout), Some((4, 20)));
+    }
+
+    #[test]
+    fn test_huge_chunk_is_size_overflow() {
+        assert!(matches!(Arena::new(usize::MAX), Err(ArenaError::SizeOverflow)));
+    }
+
+    mod props {
+        use super::*;
+        use proptest::prelude::*;
+
+        fn extreme_usize() -> impl Strategy<Value = usize> {
+            prop_oneof![
+                0..=4096usize,
+                Just(usize::MAX),
+                Just(isize::MAX as usize),
+                (0..usize::BITS).prop_map(|shift| 1usize << shift),
+                (0..usize::BITS).prop_map(|shift| (1usize << shift).wrapping_sub(1)),
+                any::<usize>(),
+            ]
+        }
+
+        fn check<T>(result: Result<&mut [T], ArenaError>, arena: &Arena, count: usize) {
+            match result {
+                Ok(slice) => {
+                    assert_eq!(slice.len(), count);
+                    assert_eq!(slice.as_ptr() as usize % align_of::<T>(), 0);
+                    assert!(arena.used() <= arena.capacity());
+                }
+                Err(e) => assert!(matches!(e, ArenaError::OutOfMemory { .. } | ArenaError::SizeOverflow)),
+            }
+        }
+
+        proptest! {
+            #[test]
+            fn alloc_array_uninit_never_wraps(count in extreme_usize(), pre in 0..64usize) {
+                let arena = Arena::new(1024).unwrap();
+                arena.alloc_array_uninit::<u8>(pre).unwrap();
+                let used = arena.used();
+
+                check(arena.alloc_array_uninit::<u8>(count).map(ArenaSlice::leak), &arena, count);
+                check(arena.alloc_array_uninit::<u64>(count).map(ArenaSlice::leak), &arena, count);
+                check(arena.alloc_array_uninit::<[u8; 4096]>(count).map(ArenaSlice::leak), &arena, count);
+                check(arena.alloc_array_uninit::<Align4096>(count).map(ArenaSlice::leak), &arena, count);
+                prop_assert!(arena.used() >= used);
+            }
+
+            #[test]
+            fn alloc_array_never_wraps(count in extreme_usize(), pre in 0..64usize) {
+                let arena = Arena::new(1024).unwrap();
+                arena.alloc_array_uninit::<u8>(pre).unwrap();
+
+                check(arena.alloc_array(count, |i| i as u32).map(ArenaSlice::leak), &arena, count);
+                check(arena.alloc_array(count, |_| Align64(0)).map(ArenaSlice::leak), &arena, count);
+            }
+
+            #[test]
+            fn growable_rejects_extreme_layouts(size in extreme_usize(), shift in 0..usize::BITS - 1) {
+                let arena = Arena::builder(64).growth(Growth::Doubling).build().unwrap();
+                let used = arena.used();
+
+                if let Ok(layout) = Layout::from_size_align(size, 1 << shift) {
+                    // Only requests the system allocator refuses outright.
+                    prop_assume!(layout.size() > isize::MAX as usize / 2 || layout.size() < 1 << 20);
+                    match arena.alloc_layout(layout) {
+                        Ok(ptr) => {
+                            prop_assert_eq!(ptr.as_ptr() as usize % layout.align(), 0);
+                            prop_assert!(arena.used() >= used + layout.size());
+                        }
+                        Err(e) => prop_assert!(matches!(
+                            e.kind(),
+                            ArenaErrorKind::SizeOverflow | ArenaErrorKind::AllocationFailed
+                        )),
+                    }
+                }
+            }
         }
-        let vals: &[u32] = unsafe { core::mem::transmute(&*slots) };
-        assert_eq!(vals, [0, 10, 20, 30]);
     }
 
+    #[derive(Debug, PartialEq)]
+    struct Unit;
+
+    #[repr(align(64))]
+    struct AlignedUnit;
+
     #[test]
-    fn test_zero_size_arena_fails() {
-        assert!(matches!(Arena::new(0), Err(ArenaError::InvalidSize)));
+    fn test_zst_alloc_does_not_bump() {
+        let arena = Arena::new(8).unwrap();
+
+        let a = arena.alloc(Unit).unwrap();
+        let b = arena.alloc(()).unwrap();
+        let c = arena.alloc(AlignedUnit).unwrap();
+        assert_eq!(*a, Unit);
+        assert_eq!(*b, ());
+        assert_eq!(addr_of(&*c) % 64, 0);
+        assert_eq!(arena.used(), 0);
+
+        // The arena is still completely free for real data.
+        arena.alloc(1u64).unwrap();
     }
 
     #[test]
-    fn test_empty_array_alloc() {
-        let mut arena = Arena::new(64).unwrap();
-        let empty = arena.alloc_array::<u32, _>(0, |_| 0).unwrap();
-        assert!(empty.is_empty());
-        assert_eq!(arena.used(), 0); // nothing consumed
+    fn test_zst_array() {
+        let arena = Arena::new(8).unwrap();
+        let mut calls = 0;
+        let units = arena.alloc_array(1_000, |_| {
+            calls += 1;
+            Unit
+        }).unwrap();
+        assert_eq!(units.len(), 1_000);
+        assert_eq!(calls, 1_000);
+        assert_eq!(arena.used(), 0);
+
+        let uninit = arena.alloc_array_uninit::<()>(usize::MAX).unwrap();
+        assert_eq!(uninit.len(), usize::MAX);
+    }
+
+    #[test]
+    fn test_zst_with_drop() {
+        static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+        struct Guard;
+
+        impl Drop for Guard {
+            fn drop(&mut self) {
+                DROPS.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let mut arena = Arena::new(256).unwrap();
+        arena.alloc_array_with_drop(3, |_| Guard).unwrap();
+        arena.alloc_with_drop(Guard).unwrap();
+
+        arena.reset();
+        assert_eq!(DROPS.load(Ordering::SeqCst), 4);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,302 @@
+use alloc::alloc::{self as allocator, Layout};
+use core::ptr::NonNull;
+
+#[cfg(all(test, loom))]
+use loom::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(not(all(test, loom)))]
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::{aligned_range, dangling, ArenaError, ArenaRef, ArenaSlice};
+
+/// A fixed-size bump allocator that can be shared between threads.
+///
+/// `SyncArena` is `Sync`: any number of threads can allocate from one buffer
+/// through `&SyncArena` at the same time. The bump offset is advanced with an
+/// atomic compare-and-swap, so every allocation gets a disjoint region
+/// without taking a lock. [`reset`] takes `&mut self` and therefore requires
+/// exclusive access, which the borrow checker enforces across threads too.
+///
+/// Like [`Arena`], `SyncArena` never runs destructors.
+///
+/// # Example
+/// ```
+/// use arenars::SyncArena;
+///
+/// let arena = SyncArena::new(1024).unwrap();
+///
+/// std::thread::scope(|s| {
+///     for t in 0..4u64 {
+///         let arena = &arena;
+///         s.spawn(move || {
+///             let values = arena.alloc_array(8, |i| t * 100 + i as u64).unwrap();
+///             assert_eq!(values[7], t * 100 + 7);
+///         });
+///     }
+/// });
+///
+/// assert_eq!(arena.used(), 4 * 8 * 8);
+/// ```
+///
+/// [`Arena`]: crate::Arena
+/// [`reset`]: SyncArena::reset
+pub struct SyncArena {
+    memory: NonNull<u8>,
+    size: usize,
+    offset: AtomicUsize,
+}
+
+// SAFETY: the buffer is owned by the arena and only reachable through it.
+// Concurrent `alloc` calls hand out disjoint regions because the offset is
+// only ever advanced with a compare-and-swap, and `reset` needs `&mut self`.
+unsafe impl Send for SyncArena {}
+unsafe impl Sync for SyncArena {}
+
+const ALIGN: usize = 8;
+
+impl SyncArena {
+    /// Create a new arena with the specified size in bytes.
+    pub fn new(size: usize) -> Result<Self, ArenaError> {
+        if size == 0 {
+            return Err(ArenaError::InvalidSize);
+        }
+
+        let layout = Layout::from_size_align(size, ALIGN)
+            .map_err(|_| ArenaError::SizeOverflow)?;
+
+        let memory = unsafe {
+            let ptr = allocator::alloc(layout);
+            if ptr.is_null() {
+                return Err(ArenaError::AllocationFailed { size, align: ALIGN });
+            }
+            NonNull::new_unchecked(ptr)
+        };
+
+        Ok(SyncArena {
+            memory,
+            size,
+            offset: AtomicUsize::new(0),
+        })
+    }
+
+    /// Allocate space for a single object of type T, initialized with `value`.
+    pub fn alloc<T>(&self, value: T) -> Result<ArenaRef<'_, T>, ArenaError> {
+        let ptr = self.alloc_layout(Layout::new::<T>())?;
+
+        unsafe {
+            let typed_ptr = ptr.as_ptr() as *mut T;
+            typed_ptr.write(value);
+            Ok(ArenaRef { inner: &mut *typed_ptr })
+        }
+    }
+
+    /// Allocate space for an array of `count` elements, each initialized by
+    /// calling `init(index)`.
+    pub fn alloc_array<T, F>(
+        &self,
+        count: usize,
+        mut init: F,
+    ) -> Result<ArenaSlice<'_, T>, ArenaError>
+    where
+        F: FnMut(usize) -> T,
+    {
+        if count == 0 {
+            return Ok(ArenaSlice::untracked(&mut []));
+        }
+
+        let layout = Layout::array::<T>(count)
+            .map_err(|_| ArenaError::SizeOverflow)?;
+        let ptr = self.alloc_layout(layout)?;
+
+        unsafe {
+            let base = ptr.as_ptr() as *mut T;
+            for i in 0..count {
+                base.add(i).write(init(i));
+            }
+            Ok(ArenaSlice::untracked(core::slice::from_raw_parts_mut(base, count)))
+        }
+    }
+
+    /// Reserve `layout` with a lock-free compare-and-swap on the offset.
+    fn alloc_layout(&self, layout: Layout) -> Result<NonNull<u8>, ArenaError> {
+        if layout.size() == 0 {
+            return Ok(dangling(layout));
+        }
+
+        let base = self.memory.as_ptr() as usize;
+
+        // Relaxed is enough: the CAS only has to make the claimed ranges
+        // disjoint. Publishing the values to other threads is up to the
+        // caller's own synchronization.
+        let mut offset = self.offset.load(Ordering::Relaxed);
+        loop {
+            let (start, end) = match aligned_range(base, offset, layout) {
+                Some((start, end)) if end <= self.size => (start, end),
+                _ => {
+                    return Err(ArenaError::OutOfMemory {
+                        size: layout.size(),
+                        align: layout.align(),
+                        remaining: self.size.saturating_sub(offset),
+                        capacity: self.size,
+                        name: None,
+                    })
+                }
+            };
+
+            match self.offset.compare_exchange_weak(offset, end, Ordering::Relaxed, Ordering::Relaxed) {
+                Ok(_) => unsafe {
+                    return Ok(NonNull::new_unchecked(self.memory.as_ptr().add(start)));
+                },
+                Err(current) => offset = current,
+            }
+        }
+    }
+
+    /// Reset the arena (doesn't deallocate, just resets the offset).
+    ///
+    /// Requires exclusive access, so no thread can still hold a reference
+    /// into the arena.
+    pub fn reset(&mut self) {
+        self.offset.store(0, Ordering::Relaxed);
+    }
+
+    /// Remaining space in bytes.
+    pub fn remaining(&self) -> usize {
+        self.size - self.used()
+    }
+
+    /// Used space in bytes.
+    pub fn used(&self) -> usize {
+        self.offset.load(Ordering::Relaxed)
+    }
+
+    /// Total capacity in bytes.
+    pub fn capacity(&self) -> usize {
+        self.size
+    }
+}
+
+impl core::fmt::Debug for SyncArena {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "SyncArena {{ used: {}, capacity: {} }}", self.used(), self.capacity())
+    }
+}
+
+impl Drop for SyncArena {
+    fn drop(&mut self) {
+        unsafe {
+            let layout = Layout::from_size_align_unchecked(self.size, ALIGN);
+            allocator::dealloc(self.memory.as_ptr(), layout);
+        }
+    }
+}
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use super::*;
+    use alloc::format;
+
+    #[test]
+    fn test_alloc_single() {
+        let arena = SyncArena::new(64).unwrap();
+        let a = arena.alloc(1u64).unwrap();
+        let b = arena.alloc(2u64).unwrap();
+        assert_eq!(*a + *b, 3);
+        assert_eq!(arena.used(), 16);
+    }
+
+    #[test]
+    fn test_out_of_memory() {
+        let arena = SyncArena::new(8).unwrap();
+        arena.alloc(0u64).unwrap();
+        assert!(matches!(arena.alloc(0u64), Err(ArenaError::OutOfMemory { .. })));
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut arena = SyncArena::new(16).unwrap();
+        arena.alloc_array(2, |_| 0u64).unwrap();
+        arena.reset();
+        assert_eq!(arena.used(), 0);
+        assert_eq!(arena.remaining(), 16);
+    }
+
+    #[test]
+    fn test_debug_format() {
+        let arena = SyncArena::new(512).unwrap();
+        assert_eq!(format!("{:?}", arena), "SyncArena { used: 0, capacity: 512 }");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_concurrent_alloc_stress() {
+        use alloc::vec::Vec;
+
+        const THREADS: usize = 8;
+        const PER_THREAD: usize = 1_000;
+        let arena = SyncArena::new(THREADS * PER_THREAD * size_of::<u64>()).unwrap();
+
+        let mut addrs: Vec<usize> = std::thread::scope(|s| {
+            let handles: Vec<_> = (0..THREADS)
+                .map(|t| {
+                    let arena = &arena;
+                    s.spawn(move || {
+                        (0..PER_THREAD)
+                            .map(|i| {
+                                let value = (t * PER_THREAD + i) as u64;
+                                let r = arena.alloc(value).unwrap();
+                                assert_eq!(*r, value);
+                                &*r as *const u64 as usize
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            handles.into_iter().flat_map(|h| h.join().unwrap()).collect()
+        });
+
+        // Every slot was handed out exactly once and the arena is now full.
+        addrs.sort_unstable();
+        addrs.dedup();
+        assert_eq!(addrs.len(), THREADS * PER_THREAD);
+        assert_eq!(arena.remaining(), 0);
+        assert!(matches!(arena.alloc(0u8), Err(ArenaError::OutOfMemory { .. })));
+    }
+}
+
+#[cfg(all(test, loom))]
+mod loom_tests {
+    use super::*;
+    use loom::sync::Arc;
+    use loom::thread;
+
+    #[test]
+    fn concurrent_allocs_are_disjoint() {
+        loom::model(|| {
+            let arena = Arc::new(SyncArena::new(16).unwrap());
+
+            let other = Arc::clone(&arena);
+            let handle = thread::spawn(move || &*other.alloc(1u64).unwrap() as *const u64 as usize);
+            let here = &*arena.alloc(2u64).unwrap() as *const u64 as usize;
+            let there = handle.join().unwrap();
+
+            assert_ne!(here, there);
+            assert_eq!(arena.used(), 16);
+            assert!(arena.alloc(3u8).is_err());
+        });
+    }
+
+    #[test]
+    fn concurrent_alloc_respects_capacity() {
+        loom::model(|| {
+            let arena = Arc::new(SyncArena::new(12).unwrap());
+
+            let other = Arc::clone(&arena);
+            let handle = thread::spawn(move || other.alloc(1u64).is_ok());
+            let here = arena.alloc(2u64).is_ok();
+            let there = handle.join().unwrap();
+
+            // Only one of the two 8-byte allocations fits.
+            assert!(here ^ there);
+        });
+    }
+}
@@ -1,7 +1,11 @@
 use alloc::alloc::{self as allocator, Layout};
+use core::pin::Pin;
 use core::ptr::{self, NonNull};
 
-use crate::ArenaError;
+use crate::{AllocError, ArenaBox, ArenaError};
+
+#[cfg(test)]
+use crate::ArenaErrorKind;
 
 /// A type-specialized arena allocator that properly calls destructors.
 ///
@@ -12,6 +16,9 @@ use crate::ArenaError;
 /// This makes it safe to allocate types that own heap resources (e.g.
 /// `String`, `Vec<T>`, `Box<T>`).
 ///
+/// Zero-sized types are supported without touching the global allocator:
+/// the arena only counts allocations and still runs their destructors.
+///
 /// # Example
 /// ```
 /// use arenars::TypedArena;
@@ -30,8 +37,21 @@ pub struct TypedArena<T> {
     memory: NonNull<T>,
     capacity: usize, // in number of T's, not bytes
     count: usize,    // number of live T's
+    // Allocated by the first `alloc_pinned`: one count per slot, 1 unless a
+    // pinned box has already dropped or given up the slot's value.
+    drop_lens: Option<NonNull<usize>>,
 }
 
+// SAFETY: the arena exclusively owns its `T`s and drops them on `reset` or
+// `Drop`, so moving the arena moves ownership of the values: sound exactly
+// when `T: Send`, as for `Vec<T>`. The drop counts of pinned boxes are only
+// touched through the box, which borrows the arena, or through `&mut self`.
+unsafe impl<T: Send> Send for TypedArena<T> {}
+
+// SAFETY: the only `&self` methods read the count and capacity; values are
+// reachable only through `&mut self`, so sharing requires at most `T: Sync`.
+unsafe impl<T: Sync> Sync for TypedArena<T> {}
+
 impl<T> TypedArena<T> {
     /// Create a new `TypedArena` that can hold up to `capacity` objects of
     /// type `T`.
@@ -40,13 +60,27 @@ impl<T> TypedArena<T> {
             return Err(ArenaError::InvalidSize);
         }
 
+        // Allocating a zero-size layout is undefined behaviour; every ZST
+        // lives at the same dangling, well-aligned address instead.
+        if size_of::<T>() == 0 {
+            return Ok(Self {
+                memory: NonNull::dangling(),
+                capacity,
+                count: 0,
+                drop_lens: None,
+            });
+        }
+
         let layout = Layout::array::<T>(capacity)
-            .map_err(|_| ArenaError::InvalidSize)?;
+            .map_err(|_| ArenaError::SizeOverflow)?;
 
         let memory = unsafe {
             let ptr = allocator::alloc(layout) as *mut T;
             if ptr.is_null() {
-                return Err(ArenaError::AllocationFailed);
+                return Err(ArenaError::AllocationFailed {
+                    size: layout.size(),
+                    align: layout.align(),
+                });
             }
             NonNull::new_unchecked(ptr)
         };
@@ -55,6 +89,7 @@ impl<T> TypedArena<T> {
             memory,
             capacity,
             count: 0,
+            drop_lens: None,
         })
     }
 
@@ -65,8 +100,16 @@ impl<T> TypedArena<T> {
     ///
     /// [`reset`]: TypedArena::reset
     pub fn alloc(&mut self, value: T) -> Result<&mut T, ArenaError> {
+        self.try_alloc(value).map_err(|e| e.error)
+    }
+
+    /// Like [`alloc`], but hands `value` back inside the error if the arena
+    /// is full, so it can be stored somewhere else.
+    ///
+    /// [`alloc`]: TypedArena::alloc
+    pub fn try_alloc(&mut self, value: T) -> Result<&mut T, AllocError<T>> {
         if self.count == self.capacity {
-            return Err(ArenaError::OutOfMemory);
+            return Err(AllocError { error: self.out_of_memory(), value });
         }
 
         unsafe {
@@ -77,14 +120,117 @@ impl<T> TypedArena<T> {
         }
     }
 
+    /// Allocate a single `T` built by `f`, writing it straight into its slot.
+    ///
+    /// Unlike [`alloc`], the value does not have to be built on the stack and
+    /// copied in, which avoids large copies for big types. If `f` panics, no
+    /// slot is used.
+    ///
+    /// [`alloc`]: TypedArena::alloc
+    #[inline(always)]
+    pub fn alloc_with<F>(&mut self, f: F) -> Result<&mut T, ArenaError>
+    where
+        F: FnOnce() -> T,
+    {
+        if self.count == self.capacity {
+            return Err(self.out_of_memory());
+        }
+
+        unsafe {
+            let slot = self.memory.as_ptr().add(self.count);
+            ptr::write(slot, f());
+            self.count += 1;
+            Ok(&mut *slot)
+        }
+    }
+
+    /// Allocate a single `T` that stays pinned in place, owned by the
+    /// returned [`ArenaBox`].
+    ///
+    /// Like [`Arena::alloc_pinned`], the value is dropped when the box goes
+    /// away; if the box is leaked with `mem::forget`, [`reset`] drops it
+    /// instead. Either way it is dropped exactly once, before its slot is
+    /// reused.
+    ///
+    /// The first call allocates one `usize` per slot to track which values
+    /// their boxes have already dropped.
+    ///
+    /// ```
+    /// # use arenars::TypedArena;
+    /// let mut arena = TypedArena::<String>::new(4).unwrap();
+    /// let name = arena.alloc_pinned("pinned".to_string()).unwrap();
+    /// assert_eq!(*name, "pinned");
+    /// drop(name); // the String is freed here, not again on reset
+    /// arena.reset();
+    /// ```
+    ///
+    /// [`Arena::alloc_pinned`]: crate::Arena::alloc_pinned
+    /// [`reset`]: TypedArena::reset
+    pub fn alloc_pinned(&mut self, value: T) -> Result<Pin<ArenaBox<'_, T>>, ArenaError> {
+        let drop_lens = match self.drop_lens {
+            Some(drop_lens) => drop_lens,
+            None => *self.drop_lens.insert(self.alloc_drop_lens()?),
+        };
+        let slot = self.count;
+        let ptr = NonNull::from(self.alloc(value)?);
+        // SAFETY: slots never move, and `reset` skips the slot once the box
+        // has cleared its count, so the value is dropped exactly once before
+        // the slot is reused. If the arena itself is leaked, so is its memory.
+        Ok(unsafe { Pin::new_unchecked(ArenaBox::from_parts(ptr, Some(drop_lens.add(slot)))) })
+    }
+
+    /// Allocate the per-slot drop counts, all set to 1.
+    #[cold]
+    fn alloc_drop_lens(&self) -> Result<NonNull<usize>, ArenaError> {
+        let layout = Layout::array::<usize>(self.capacity)
+            .map_err(|_| ArenaError::SizeOverflow)?;
+
+        unsafe {
+            let Some(drop_lens) = NonNull::new(allocator::alloc(layout) as *mut usize) else {
+                return Err(ArenaError::AllocationFailed {
+                    size: layout.size(),
+                    align: layout.align(),
+                });
+            };
+            for i in 0..self.capacity {
+                drop_lens.add(i).write(1);
+            }
+            Ok(drop_lens)
+        }
+    }
+
+    /// The error for an allocation into a full arena.
+    #[cold]
+    fn out_of_memory(&self) -> ArenaError {
+        ArenaError::OutOfMemory {
+            size: size_of::<T>(),
+            align: align_of::<T>(),
+            remaining: 0,
+            capacity: self.capacity.saturating_mul(size_of::<T>()),
+            name: None,
+        }
+    }
+
     /// Drop all allocated objects and reset the arena for reuse.
     ///
     /// Calls `drop_in_place` on every live `T` in allocation order, then
     /// resets the count to zero. The backing memory is retained.
+    ///
+    /// Values from [`alloc_pinned`](TypedArena::alloc_pinned) whose box has
+    /// already dropped or given them up are skipped.
     pub fn reset(&mut self) {
         unsafe {
             let base = self.memory.as_ptr();
             for i in 0..self.count {
+                // A pinned box clears its slot's count once it has dropped or
+                // given up the value; re-arm the slot for its next use.
+                if let Some(drop_lens) = self.drop_lens {
+                    let len = drop_lens.add(i);
+                    if len.read() == 0 {
+                        len.write(1);
+                        continue;
+                    }
+                }
                 ptr::drop_in_place(base.add(i));
             }
         }
@@ -128,12 +274,18 @@ impl<T> Drop for TypedArena<T> {
         self.reset();
 
         unsafe {
-            // capacity == 0 is prevented by new(), but guard anyway
-            if self.capacity > 0 {
+            // capacity == 0 is prevented by new(), but guard anyway; ZSTs
+            // never allocated anything.
+            if self.capacity > 0 && size_of::<T>() > 0 {
                 let layout = Layout::array::<T>(self.capacity)
                     .expect("layout valid: same params used in new()");
                 allocator::dealloc(self.memory.as_ptr() as *mut u8, layout);
             }
+            if let Some(drop_lens) = self.drop_lens {
+                let layout = Layout::array::<usize>(self.capacity)
+                    .expect("layout valid: same params used in alloc_drop_lens()");
+                allocator::dealloc(drop_lens.as_ptr() as *mut u8, layout);
+            }
         }
     }
 }
@@ -218,7 +370,78 @@ mod tests {
         let mut arena = TypedArena::<u32>::new(2).unwrap();
         arena.alloc(1).unwrap();
         arena.alloc(2).unwrap();
-        assert!(matches!(arena.alloc(3), Err(ArenaError::OutOfMemory)));
+        assert!(matches!(arena.alloc(3), Err(ArenaError::OutOfMemory { .. })));
+    }
+
+    #[test]
+    fn test_alloc_with() {
+        let live = Arc::new(AtomicUsize::new(0));
+        let mut arena = TypedArena::new(1).unwrap();
+
+        let value = arena.alloc_with(|| DropCounter::new(&live)).unwrap();
+        assert_eq!(value.live.load(Ordering::SeqCst), 1);
+        assert!(matches!(arena.alloc_with(|| unreachable!()), Err(ArenaError::OutOfMemory { .. })));
+
+        drop(arena);
+        assert_eq!(live.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_alloc_pinned() {
+        use core::marker::PhantomPinned;
+
+        // Checks on drop that it was never moved after being pinned.
+        struct SelfRef {
+            this: *const SelfRef,
+            _pin: PhantomPinned,
+        }
+
+        impl Drop for SelfRef {
+            fn drop(&mut self) {
+                assert_eq!(self.this, self as *const SelfRef);
+            }
+        }
+
+        let mut arena = TypedArena::new(2).unwrap();
+        let mut pinned = arena.alloc_pinned(SelfRef { this: ptr::null(), _pin: PhantomPinned }).unwrap();
+        let this = &*pinned as *const SelfRef;
+        unsafe { pinned.as_mut().get_unchecked_mut().this = this };
+        core::mem::forget(pinned);
+        arena.reset(); // dropped here, in place
+    }
+
+    #[test]
+    fn test_alloc_pinned_dropped_once() {
+        let live = Arc::new(AtomicUsize::new(0));
+        let mut arena = TypedArena::new(4).unwrap();
+
+        arena.alloc(DropCounter::new(&live)).unwrap();
+        drop(arena.alloc_pinned(DropCounter::new(&live)).unwrap());
+        assert_eq!(live.load(Ordering::SeqCst), 1);
+        core::mem::forget(arena.alloc_pinned(DropCounter::new(&live)).unwrap());
+        let moved = Pin::into_inner(arena.alloc_pinned(DropCounter::new(&live)).unwrap()).into_inner();
+        assert_eq!(live.load(Ordering::SeqCst), 3);
+
+        arena.reset(); // drops the plain and the forgotten value only
+        assert_eq!(live.load(Ordering::SeqCst), 1);
+        drop(moved);
+        assert_eq!(live.load(Ordering::SeqCst), 0);
+
+        // Released slots are dropped again once reused.
+        arena.alloc(DropCounter::new(&live)).unwrap();
+        arena.alloc(DropCounter::new(&live)).unwrap();
+        arena.reset();
+        assert_eq!(live.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_try_alloc_returns_value() {
+        let mut arena = TypedArena::<String>::new(1).unwrap();
+        arena.try_alloc("first".to_string()).unwrap();
+
+        let err = arena.try_alloc("second".to_string()).unwrap_err();
+        assert_eq!(err.error.kind(), ArenaErrorKind::OutOfMemory);
+        assert_eq!(err.into_inner(), "second");
     }
 
     #[test]
@@ -232,4 +455,73 @@ mod tests {
         arena.alloc(1).unwrap();
         assert_eq!(format!("{:?}", arena), "TypedArena { len: 1, capacity: 8 }");
     }
-}
\ No newline at end of file
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_move_to_thread() {
+        let live = Arc::new(AtomicUsize::new(0));
+        let mut arena = TypedArena::new(4).unwrap();
+        arena.alloc(DropCounter::new(&live)).unwrap();
+
+        let worker_live = Arc::clone(&live);
+        let arena = std::thread::spawn(move || {
+            arena.alloc(DropCounter::new(&worker_live)).unwrap();
+            arena
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(arena.len(), 2);
+        drop(arena);
+        assert_eq!(live.load(Ordering::SeqCst), 0);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_shared_between_threads() {
+        let mut arena = TypedArena::<String>::new(4).unwrap();
+        arena.alloc("shared".to_string()).unwrap();
+
+        std::thread::scope(|s| {
+            let arena = &arena;
+            s.spawn(move || assert_eq!(arena.len(), 1));
+            s.spawn(move || assert_eq!(arena.remaining(), 3));
+        });
+    }
+
+    #[test]
+    fn test_zst_counts_allocations() {
+        let mut arena = TypedArena::<()>::new(3).unwrap();
+        arena.alloc(()).unwrap();
+        arena.alloc(()).unwrap();
+        arena.alloc(()).unwrap();
+        assert_eq!(arena.len(), 3);
+        assert!(matches!(arena.alloc(()), Err(ArenaError::OutOfMemory { .. })));
+
+        arena.reset();
+        assert!(arena.is_empty());
+    }
+
+    #[test]
+    fn test_zst_runs_drops() {
+        static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+        struct Guard;
+
+        impl Drop for Guard {
+            fn drop(&mut self) {
+                DROPS.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let mut arena = TypedArena::new(usize::MAX).unwrap();
+        arena.alloc(Guard).unwrap();
+        arena.alloc(Guard).unwrap();
+        arena.reset();
+        assert_eq!(DROPS.load(Ordering::SeqCst), 2);
+
+        arena.alloc(Guard).unwrap();
+        drop(arena);
+        assert_eq!(DROPS.load(Ordering::SeqCst), 3);
+    }
+}
@@ -0,0 +1,307 @@
+//! Support for turning `ArenaBox<T>` and `ArenaRef<T>` into pointers to
+//! unsized types such as `dyn Trait` or `[T]`.
+//!
+//! On nightly with the `nightly` feature, both types implement
+//! `CoerceUnsized`, so the conversion happens implicitly like it does for
+//! `Box`. On stable, the [`arena_unsize!`](crate::arena_unsize) macro does the
+//! same. The items in this module are its implementation details.
+
+use core::marker::PhantomData;
+use core::pin::Pin;
+use core::ptr::NonNull;
+
+use crate::{ArenaBox, ArenaRef};
+
+/// A pointer that can be taken apart into a raw pointer and rebuilt from it
+/// by [`arena_unsize!`](crate::arena_unsize).
+///
+/// The trait is sealed: the macro trusts that the pointer and token belong
+/// together, so only the arena's own pointer types implement it.
+///
+/// ```compile_fail
+/// use arenars::unsize::{BoxToken, IntoRawParts};
+/// use arenars::{Arena, ArenaBox};
+///
+/// struct Forged<'a>(ArenaBox<'a, u8>);
+///
+/// impl<'a> IntoRawParts for Forged<'a> {
+///     type Target = u64;
+///     type Token = BoxToken<'a>;
+///
+///     fn into_raw_parts(self) -> (*mut u64, BoxToken<'a>) {
+///         (core::ptr::null_mut(), self.0.into_raw_parts().1)
+///     }
+/// }
+/// ```
+pub trait IntoRawParts: sealed::Sealed {
+    type Target: ?Sized;
+    type Token;
+
+    fn into_raw_parts(self) -> (*mut Self::Target, Self::Token);
+}
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl<T: ?Sized> Sealed for crate::ArenaBox<'_, T> {}
+    impl<T: ?Sized> Sealed for core::pin::Pin<crate::ArenaBox<'_, T>> {}
+    impl<T: ?Sized> Sealed for crate::ArenaRef<'_, T> {}
+}
+
+/// Remembers the lifetime and drop count of an [`ArenaBox`] taken apart by
+/// [`arena_unsize!`](crate::arena_unsize).
+pub struct BoxToken<'a>(Option<NonNull<usize>>, PhantomData<&'a ()>);
+
+/// Like [`BoxToken`], for a pinned [`ArenaBox`].
+pub struct PinBoxToken<'a>(BoxToken<'a>);
+
+/// Remembers the lifetime of an [`ArenaRef`] taken apart by
+/// [`arena_unsize!`](crate::arena_unsize).
+pub struct RefToken<'a>(PhantomData<&'a mut ()>);
+
+impl<'a, T: ?Sized> IntoRawParts for ArenaBox<'a, T> {
+    type Target = T;
+    type Token = BoxToken<'a>;
+
+    fn into_raw_parts(self) -> (*mut T, BoxToken<'a>) {
+        let (ptr, drop_len) = self.into_parts();
+        (ptr.as_ptr(), BoxToken(drop_len, PhantomData))
+    }
+}
+
+impl<'a, T: ?Sized> IntoRawParts for Pin<ArenaBox<'a, T>> {
+    type Target = T;
+    type Token = PinBoxToken<'a>;
+
+    fn into_raw_parts(self) -> (*mut T, PinBoxToken<'a>) {
+        // SAFETY: `PinBoxToken::rebuild` pins the value again without it
+        // having moved.
+        let (ptr, token) = unsafe { Pin::into_inner_unchecked(self) }.into_raw_parts();
+        (ptr, PinBoxToken(token))
+    }
+}
+
+impl<'a, T: ?Sized> IntoRawParts for ArenaRef<'a, T> {
+    type Target = T;
+    type Token = RefToken<'a>;
+
+    fn into_raw_parts(self) -> (*mut T, RefToken<'a>) {
+        (self.inner as *mut T, RefToken(PhantomData))
+    }
+}
+
+impl<'a> BoxToken<'a> {
+    /// # Safety
+    /// `ptr` must be the pointer returned alongside this token, possibly
+    /// coerced to an unsized type.
+    pub unsafe fn rebuild<U: ?Sized>(self, ptr: *mut U) -> ArenaBox<'a, U> {
+        unsafe { ArenaBox::from_parts(NonNull::new_unchecked(ptr), self.0) }
+    }
+}
+
+impl<'a> PinBoxToken<'a> {
+    /// # Safety
+    /// `ptr` must be the pointer returned alongside this token, possibly
+    /// coerced to an unsized type.
+    pub unsafe fn rebuild<U: ?Sized>(self, ptr: *mut U) -> Pin<ArenaBox<'a, U>> {
+        unsafe { Pin::new_unchecked(self.0.rebuild(ptr)) }
+    }
+}
+
+impl<'a> RefToken<'a> {
+    /// # Safety
+    /// `ptr` must be the pointer returned alongside this token, possibly
+    /// coerced to an unsized type.
+    pub unsafe fn rebuild<U: ?Sized>(self, ptr: *mut U) -> ArenaRef<'a, U> {
+        ArenaRef { inner: unsafe { &mut *ptr } }
+    }
+}
+
+/// Convert an [`ArenaBox`], a pinned `ArenaBox` or an [`ArenaRef`] to one
+/// pointing at an unsized type, such as a trait object or a slice.
+///
+/// This is the stable counterpart of the implicit coercion that the `nightly`
+/// feature enables. The target type is taken from context, and only
+/// conversions the compiler would allow for `Box` are accepted. Dropping an
+/// `ArenaBox<dyn Trait>` runs the destructor of the original type.
+///
+/// ```
+/// use arenars::{arena_unsize, Arena, ArenaBox, ArenaRef};
+/// use core::fmt::Debug;
+///
+/// let arena = Arena::new(1024).unwrap();
+///
+/// let offset = 10;
+/// let add: ArenaBox<'_, dyn Fn(i32) -> i32> =
+///     arena_unsize!(arena.alloc_box(move |x| x + offset).unwrap());
+/// assert_eq!(add(5), 15);
+///
+/// let items: ArenaRef<'_, [u8]> = arena_unsize!(arena.alloc([1u8, 2, 3]).unwrap());
+/// assert_eq!(items.len(), 3);
+///
+/// let shown: Vec<ArenaBox<'_, dyn Debug>> = vec![
+///     arena_unsize!(arena.alloc_box(1u8).unwrap()),
+///     arena_unsize!(arena.alloc_box("two").unwrap()),
+/// ];
+/// assert_eq!(format!("{:?}", shown), r#"[1, "two"]"#);
+/// ```
+///
+/// Conversions that are not unsizing coercions do not compile:
+///
+/// ```compile_fail
+/// use arenars::{arena_unsize, Arena, ArenaBox};
+///
+/// let arena = Arena::new(64).unwrap();
+/// let wider: ArenaBox<'_, u64> = arena_unsize!(arena.alloc_box(1u32).unwrap());
+/// ```
+#[macro_export]
+macro_rules! arena_unsize {
+    ($ptr:expr) => {{
+        let (raw, token) = $crate::unsize::IntoRawParts::into_raw_parts($ptr);
+        // SAFETY: `raw` is the pointer that came with `token`. Passing it as
+        // an argument only allows implicit coercions, so at most it was
+        // unsized on the way in.
+        #[allow(unused_unsafe)]
+        unsafe {
+            token.rebuild(raw)
+        }
+    }};
+}
+
+#[cfg(feature = "nightly")]
+impl<'a, T, U> core::ops::CoerceUnsized<ArenaBox<'a, U>> for ArenaBox<'a, T>
+where
+    T: ?Sized + core::marker::Unsize<U>,
+    U: ?Sized,
+{
+}
+
+// SAFETY: an `ArenaBox` always derefs to the same value, so coercing a
+// pinned box keeps that value pinned.
+#[cfg(feature = "nightly")]
+unsafe impl<T: ?Sized> core::pin::PinCoerceUnsized for ArenaBox<'_, T> {}
+
+#[cfg(feature = "nightly")]
+impl<'a, T, U> core::ops::CoerceUnsized<ArenaRef<'a, U>> for ArenaRef<'a, T>
+where
+    T: ?Sized + core::marker::Unsize<U>,
+    U: ?Sized,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Arena, ArenaBox, ArenaRef};
+    use alloc::format;
+    use alloc::string::String;
+    use core::any::Any;
+    use core::fmt::Display;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    trait Visitor {
+        fn visit(&mut self, n: u32);
+        fn total(&self) -> u32;
+    }
+
+    struct Sum(u32);
+
+    impl Visitor for Sum {
+        fn visit(&mut self, n: u32) {
+            self.0 += n;
+        }
+        fn total(&self) -> u32 {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_box_to_trait_object() {
+        let arena = Arena::new(256).unwrap();
+        let mut visitor: ArenaBox<'_, dyn Visitor> = arena_unsize!(arena.alloc_box(Sum(0)).unwrap());
+        for n in 1..=4 {
+            visitor.visit(n);
+        }
+        assert_eq!(visitor.total(), 10);
+    }
+
+    #[test]
+    fn test_ref_to_trait_object_and_slice() {
+        let arena = Arena::new(256).unwrap();
+        let shown: ArenaRef<'_, dyn Display> = arena_unsize!(arena.alloc(42u16).unwrap());
+        assert_eq!(format!("{}", &*shown), "42");
+
+        let mut slice: ArenaRef<'_, [u32]> = arena_unsize!(arena.alloc([3, 1, 2]).unwrap());
+        slice.sort();
+        assert_eq!(*slice, [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_erased_drop_glue() {
+        static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+        struct Guard(#[allow(dead_code)] String);
+
+        impl Drop for Guard {
+            fn drop(&mut self) {
+                DROPS.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let mut arena = Arena::new(256).unwrap();
+        {
+            let erased: ArenaBox<'_, dyn Any> = arena_unsize!(arena.alloc_box(Guard("x".into())).unwrap());
+            assert!(erased.is::<Guard>());
+        }
+        assert_eq!(DROPS.load(Ordering::SeqCst), 1);
+        arena.reset();
+    }
+
+    #[test]
+    fn test_pinned_future_to_trait_object() {
+        use alloc::sync::Arc;
+        use core::future::Future;
+        use core::pin::Pin;
+        use core::task::{Context, Poll, Waker};
+
+        type Task<'a> = Pin<ArenaBox<'a, dyn Future<Output = usize> + Send>>;
+
+        let live = Arc::new(());
+        let mut arena = Arena::new(256).unwrap();
+        {
+            let held = Arc::clone(&live);
+            let mut polled: Task<'_> = arena_unsize!(arena.alloc_pinned(async move { Arc::strong_count(&held) }).unwrap());
+            let mut cx = Context::from_waker(Waker::noop());
+            assert_eq!(polled.as_mut().poll(&mut cx), Poll::Ready(2));
+
+            let held = Arc::clone(&live);
+            let forgotten: Task<'_> = arena_unsize!(arena.alloc_pinned(async move { Arc::strong_count(&held) }).unwrap());
+            core::mem::forget(forgotten);
+        }
+        // The drop entry survived the conversion, so reset still drops it.
+        assert_eq!(Arc::strong_count(&live), 2);
+        arena.reset();
+        assert_eq!(Arc::strong_count(&live), 1);
+    }
+
+    #[test]
+    fn test_same_type_is_a_no_op() {
+        let arena = Arena::new(64).unwrap();
+        let boxed: ArenaBox<'_, u8> = arena_unsize!(arena.alloc_box(7u8).unwrap());
+        assert_eq!(*boxed, 7);
+    }
+
+    #[cfg(feature = "nightly")]
+    #[test]
+    fn test_implicit_coercion() {
+        let arena = Arena::new(256).unwrap();
+        let f: ArenaBox<'_, dyn Fn() -> u8> = arena.alloc_box(|| 9).unwrap();
+        assert_eq!(f(), 9);
+
+        let r: ArenaRef<'_, [u8]> = arena.alloc([1u8, 2]).unwrap();
+        assert_eq!(r.len(), 2);
+
+        let p: core::pin::Pin<ArenaBox<'_, dyn core::future::Future<Output = u8> + Send>> =
+            arena.alloc_pinned(async { 3 }).unwrap();
+        drop(p);
+    }
+}